@@ -0,0 +1,742 @@
+//! Backlogs, boards and taskboards of a team
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/work/boards?view=azure-devops-rest-5.1
+use std::collections::HashMap;
+
+use futures::TryFutureExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::ErrorKind;
+use crate::json_patch::JsonPatch;
+use crate::wiql::WorkItemLink;
+use crate::work_item_types::WorkItemFieldReference;
+use crate::work_items::{WorkItem, WorkItemUpdateOptions};
+use crate::{AzureClient, Future, Values};
+
+/// Version of the taskboard APIs, which only exist as of 7.1
+const TASKBOARD_API_VERSION: &str = "api-version=7.1-preview.1";
+
+/// Backlogs, boards and taskboards of a team
+pub struct Boards {
+    ops: AzureClient,
+    project: String,
+    team: String,
+}
+
+impl Boards {
+    #[doc(hidden)]
+    pub fn new<P, T>(ops: AzureClient, project: P, team: T) -> Self
+    where
+        P: Into<String>,
+        T: Into<String>,
+    {
+        Boards {
+            ops,
+            project: project.into(),
+            team: team.into(),
+        }
+    }
+
+    /// Backlog levels of the team, from portfolio backlogs down to tasks
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/backlogs?api-version=5.1-preview.1
+    pub fn backlogs(&self) -> Future<Vec<BacklogLevelConfiguration>> {
+        let uri = format!(
+            "{}?{}",
+            self.path("/backlogs"),
+            self.ops.api_version.preview(1)
+        );
+        self.ops.get_values(&uri)
+    }
+
+    /// Work items of a backlog level, in backlog order
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/backlogs/{backlogId}/workItems?api-version=5.1-preview.1
+    pub fn backlog_work_items(&self, backlog_id: &str) -> Future<BacklogLevelWorkItems> {
+        self.ops.get(&format!(
+            "{}?{}",
+            self.path(&format!("/backlogs/{}/workItems", backlog_id)),
+            self.ops.api_version.preview(1)
+        ))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/boards?api-version=5.1
+    pub fn list(&self) -> Future<Vec<BoardReference>> {
+        self.ops.get_values(&self.path("/boards"))
+    }
+
+    /// Get a board by name or id, e.g. `Stories`
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/boards/{id}?api-version=5.1
+    pub fn get(&self, board: &str) -> Future<Board> {
+        self.ops.get(&self.board_path(board, ""))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/boards/{board}/columns?api-version=5.1
+    pub fn columns(&self, board: &str) -> Future<Vec<BoardColumn>> {
+        self.ops.get_values(&self.board_path(board, "/columns"))
+    }
+
+    /// Replace the columns of a board, columns without an id being created
+    ///
+    /// PUT https://dev.azure.com/{organization}/{project}/{team}/_apis/work/boards/{board}/columns?api-version=5.1
+    pub fn update_columns(&self, board: &str, columns: &[BoardColumn]) -> Future<Vec<BoardColumn>> {
+        self.put_values(&self.board_path(board, "/columns"), columns)
+    }
+
+    /// Swimlanes of a board, the default one having no name
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/boards/{board}/rows?api-version=5.1
+    pub fn rows(&self, board: &str) -> Future<Vec<BoardRow>> {
+        self.ops.get_values(&self.board_path(board, "/rows"))
+    }
+
+    /// PUT https://dev.azure.com/{organization}/{project}/{team}/_apis/work/boards/{board}/rows?api-version=5.1
+    pub fn update_rows(&self, board: &str, rows: &[BoardRow]) -> Future<Vec<BoardRow>> {
+        self.put_values(&self.board_path(board, "/rows"), rows)
+    }
+
+    /// Styling rules of the cards of a board
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/boards/{board}/cardrulesettings?api-version=5.1
+    pub fn card_rules(&self, board: &str) -> Future<BoardCardRuleSettings> {
+        self.ops.get(&self.board_path(board, "/cardrulesettings"))
+    }
+
+    /// PATCH https://dev.azure.com/{organization}/{project}/{team}/_apis/work/boards/{board}/cardrulesettings?api-version=5.1
+    pub fn update_card_rules(
+        &self,
+        board: &str,
+        rules: &BoardCardRuleSettings,
+    ) -> Future<BoardCardRuleSettings> {
+        self.ops
+            .patch(&self.board_path(board, "/cardrulesettings"), json!(rules))
+    }
+
+    /// Move a work item to a column, and optionally a swimlane, of a board
+    ///
+    /// Board columns are stored in fields specific to each board, so the board is fetched first to find them.
+    pub fn move_to_column(
+        &self,
+        board: &str,
+        work_item_id: usize,
+        column: &str,
+        row: Option<&str>,
+    ) -> Future<WorkItem> {
+        let ops = self.ops.clone();
+        let board = self.get(board);
+        let column = column.to_owned();
+        let row = row.map(str::to_owned);
+        Box::pin(async move {
+            let board = board.await?;
+            let mut patch = JsonPatch::builder();
+            let name = board.name;
+            let column_field = board
+                .fields
+                .column_field
+                .ok_or_else(|| ErrorKind::Msg(format!("board {} has no column field", name)))?;
+            patch.add_field(column_field.reference_name, column);
+            if let Some(row) = row {
+                let row_field = board
+                    .fields
+                    .row_field
+                    .ok_or_else(|| ErrorKind::Msg(format!("board {} has no row field", name)))?;
+                patch.add_field(row_field.reference_name, row);
+            }
+            ops.update_work_item(
+                work_item_id,
+                &patch.build(),
+                &WorkItemUpdateOptions::default(),
+            )
+            .await
+        })
+    }
+
+    /// Reorder work items on the team's product backlog
+    ///
+    /// PATCH https://dev.azure.com/{organization}/{project}/{team}/_apis/work/workitemsorder?api-version=5.1
+    pub fn reorder_backlog(&self, operation: &ReorderOperation) -> Future<Vec<ReorderResult>> {
+        self.patch_values(&self.path("/workitemsorder"), operation)
+    }
+
+    /// Reorder work items within an iteration
+    ///
+    /// PATCH https://dev.azure.com/{organization}/{project}/{team}/_apis/work/iterations/{iterationId}/workitemsorder?api-version=5.1
+    pub fn reorder_iteration(
+        &self,
+        iteration_id: &str,
+        operation: &ReorderOperation,
+    ) -> Future<Vec<ReorderResult>> {
+        self.patch_values(
+            &self.path(&format!("/iterations/{}/workitemsorder", iteration_id)),
+            operation,
+        )
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/taskboardcolumns?api-version=7.1-preview.1
+    pub fn taskboard_columns(&self) -> Future<TaskboardColumns> {
+        self.ops.get(&format!(
+            "{}?{}",
+            self.path("/taskboardcolumns"),
+            TASKBOARD_API_VERSION
+        ))
+    }
+
+    /// PUT https://dev.azure.com/{organization}/{project}/{team}/_apis/work/taskboardcolumns?api-version=7.1-preview.1
+    pub fn update_taskboard_columns(
+        &self,
+        columns: &[TaskboardColumn],
+    ) -> Future<TaskboardColumns> {
+        let body = serde_json::json!({ "columns": columns });
+        self.ops.put(
+            &format!(
+                "{}?{}",
+                self.path("/taskboardcolumns"),
+                TASKBOARD_API_VERSION
+            ),
+            json!(body),
+        )
+    }
+
+    /// Column of each work item on the taskboard of an iteration
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/taskboardworkitems/{iterationId}?api-version=7.1-preview.1
+    pub fn taskboard_work_items(&self, iteration_id: &str) -> Future<Vec<TaskboardWorkItemColumn>> {
+        self.ops.get_values(&format!(
+            "{}?{}",
+            self.path(&format!("/taskboardworkitems/{}", iteration_id)),
+            TASKBOARD_API_VERSION
+        ))
+    }
+
+    /// PATCH https://dev.azure.com/{organization}/{project}/{team}/_apis/work/taskboardworkitems/{iterationId}/{workItemId}?api-version=7.1-preview.1
+    pub fn move_taskboard_work_item(
+        &self,
+        iteration_id: &str,
+        work_item_id: usize,
+        column: &str,
+    ) -> Future<Option<serde_json::Value>> {
+        let body = serde_json::json!({ "newColumn": column });
+        self.ops.patch(
+            &format!(
+                "{}?{}",
+                self.path(&format!(
+                    "/taskboardworkitems/{}/{}",
+                    iteration_id, work_item_id
+                )),
+                TASKBOARD_API_VERSION
+            ),
+            json!(body),
+        )
+    }
+
+    fn put_values<T>(&self, uri: &str, values: &[T]) -> Future<Vec<T>>
+    where
+        T: Serialize + DeserializeOwned + 'static + Send,
+    {
+        Box::pin(
+            self.ops
+                .put::<Values<T>>(uri, json!(values))
+                .map_ok(|values| values.value),
+        )
+    }
+
+    fn patch_values(&self, uri: &str, operation: &ReorderOperation) -> Future<Vec<ReorderResult>> {
+        Box::pin(
+            self.ops
+                .patch::<Values<ReorderResult>>(uri, json!(operation))
+                .map_ok(|values| values.value),
+        )
+    }
+
+    fn board_path(&self, board: &str, more: &str) -> String {
+        self.path(&format!("/boards/{}{}", board, more))
+    }
+
+    fn path(&self, more: &str) -> String {
+        format!(
+            "/{}/{}/{}/_apis/work{}",
+            self.ops.org, self.project, self.team, more
+        )
+    }
+}
+
+/// A backlog level, e.g. `Stories` or `Epics`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BacklogLevelConfiguration {
+    /// category reference name, e.g. `Microsoft.RequirementCategory`
+    pub id: String,
+    pub name: String,
+    pub rank: i32,
+    #[serde(rename = "type")]
+    pub backlog_type: BacklogType,
+    pub color: Option<String>,
+    #[serde(default)]
+    pub is_hidden: bool,
+    pub work_item_count_limit: Option<u32>,
+    #[serde(default)]
+    pub work_item_types: Vec<WorkItemTypeReference>,
+    pub default_work_item_type: Option<WorkItemTypeReference>,
+    #[serde(default)]
+    pub add_panel_fields: Vec<WorkItemFieldReference>,
+    #[serde(default)]
+    pub column_fields: Vec<BacklogColumn>,
+}
+
+/// enum representation of the kinds of backlog levels
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BacklogType {
+    Portfolio,
+    Requirement,
+    Task,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct WorkItemTypeReference {
+    pub name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BacklogColumn {
+    pub column_field_reference: WorkItemFieldReference,
+    pub width: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BacklogLevelWorkItems {
+    #[serde(default)]
+    pub work_items: Vec<WorkItemLink>,
+}
+
+impl BacklogLevelWorkItems {
+    /// ids of the work items, in backlog order
+    pub fn ids(&self) -> Vec<usize> {
+        self.work_items
+            .iter()
+            .filter_map(|link| link.target.as_ref())
+            .map(|target| target.id)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct BoardReference {
+    pub id: String,
+    pub name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Board {
+    pub id: String,
+    pub name: String,
+    pub revision: Option<i64>,
+    #[serde(default)]
+    pub is_valid: bool,
+    #[serde(default)]
+    pub can_edit: bool,
+    #[serde(default)]
+    pub columns: Vec<BoardColumn>,
+    #[serde(default)]
+    pub rows: Vec<BoardRow>,
+    pub fields: BoardFields,
+    pub url: Option<String>,
+}
+
+impl Board {
+    /// the column work items of type `work_item_type` go to when in `state`
+    pub fn column_for_state(&self, work_item_type: &str, state: &str) -> Option<&BoardColumn> {
+        self.columns.iter().find(|column| {
+            column
+                .state_mappings
+                .get(work_item_type)
+                .map(String::as_str)
+                == Some(state)
+        })
+    }
+}
+
+/// Fields storing the board column, swimlane and done state of work items
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardFields {
+    pub column_field: Option<WorkItemFieldReference>,
+    pub row_field: Option<WorkItemFieldReference>,
+    pub done_field: Option<WorkItemFieldReference>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardColumn {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    /// work in progress limit, 0 for none
+    #[serde(default)]
+    pub item_limit: u32,
+    /// state each work item type is in while in the column
+    #[serde(default)]
+    pub state_mappings: HashMap<String, String>,
+    #[serde(default)]
+    pub is_split: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub column_type: Option<BoardColumnType>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BoardColumnType {
+    Incoming,
+    InProgress,
+    Outgoing,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct BoardRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// `None` for the default swimlane
+    pub name: Option<String>,
+}
+
+/// Card rules by type, e.g. `fill` or `tagStyle`
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct BoardCardRuleSettings {
+    #[serde(default)]
+    pub rules: HashMap<String, Vec<Rule>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub name: String,
+    #[serde(
+        default,
+        deserialize_with = "bool_or_string",
+        serialize_with = "bool_as_string"
+    )]
+    pub is_enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clauses: Vec<FilterClause>,
+    #[serde(default)]
+    pub settings: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterClause {
+    pub field_name: String,
+    pub index: u32,
+    /// `AND` or `OR`, empty for the first clause
+    pub logical_operator: String,
+    pub operator: String,
+    pub value: String,
+}
+
+/// card rules carry their enabled flag as a string
+fn bool_or_string<'de, D>(deserializer: D) -> std::result::Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrString {
+        Bool(bool),
+        String(String),
+    }
+    Ok(match BoolOrString::deserialize(deserializer)? {
+        BoolOrString::Bool(value) => value,
+        BoolOrString::String(value) => value.eq_ignore_ascii_case("true"),
+    })
+}
+
+fn bool_as_string<S>(value: &bool, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(if *value { "true" } else { "false" })
+}
+
+/// Moves work items between two others, or under a parent
+///
+/// https://docs.microsoft.com/en-us/rest/api/azure/devops/work/workitemsorder/reorder%20backlog%20work%20items?view=azure-devops-rest-5.1#reorderoperation
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderOperation {
+    ids: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iteration_path: Option<String>,
+    /// 0 when the work items go last
+    next_id: usize,
+    /// 0 when the work items go first
+    previous_id: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_id: Option<usize>,
+}
+
+impl ReorderOperation {
+    pub fn builder<I>(ids: I) -> ReorderOperationBuilder
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        ReorderOperationBuilder(ReorderOperation {
+            ids: ids.into_iter().collect(),
+            ..Default::default()
+        })
+    }
+}
+
+pub struct ReorderOperationBuilder(ReorderOperation);
+
+impl ReorderOperationBuilder {
+    /// place the work items right after this one
+    pub fn after(&mut self, previous_id: usize) -> &mut Self {
+        self.0.previous_id = previous_id;
+        self
+    }
+
+    /// place the work items right before this one
+    pub fn before(&mut self, next_id: usize) -> &mut Self {
+        self.0.next_id = next_id;
+        self
+    }
+
+    /// reorder among the children of this work item
+    pub fn parent(&mut self, parent_id: usize) -> &mut Self {
+        self.0.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn iteration_path<P>(&mut self, iteration_path: P) -> &mut Self
+    where
+        P: Into<String>,
+    {
+        self.0.iteration_path = Some(iteration_path.into());
+        self
+    }
+
+    pub fn build(&self) -> ReorderOperation {
+        self.0.clone()
+    }
+}
+
+/// The new `StackRank` or `BacklogPriority` of a reordered work item
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ReorderResult {
+    pub id: usize,
+    pub order: f64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskboardColumns {
+    #[serde(default)]
+    pub columns: Vec<TaskboardColumn>,
+    #[serde(default)]
+    pub is_customized: bool,
+    #[serde(default)]
+    pub is_valid: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskboardColumn {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub order: i32,
+    #[serde(default)]
+    pub mappings: Vec<TaskboardColumnMapping>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskboardColumnMapping {
+    pub state: String,
+    pub work_item_type: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskboardWorkItemColumn {
+    pub work_item_id: usize,
+    pub column: String,
+    pub column_id: Option<String>,
+    pub state: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    fn fixture(name: &str) -> serde_json::Value {
+        let path = format!(
+            "{}/tests/fixtures/boards/{}.json",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn boards(server: &MockServer) -> Boards {
+        AzureClient::host(server.uri(), "azure-rust", "org", None)
+            .unwrap()
+            .project("project")
+            .boards("team")
+    }
+
+    #[tokio::test]
+    async fn list_backlog_levels() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/org/project/team/_apis/work/backlogs"))
+            .and(query_param("api-version", "5.1-preview.1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture("backlogs")))
+            .mount(&server)
+            .await;
+
+        let backlogs = boards(&server).backlogs().await.unwrap();
+        let types: Vec<BacklogType> = backlogs.iter().map(|b| b.backlog_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                BacklogType::Portfolio,
+                BacklogType::Requirement,
+                BacklogType::Task
+            ]
+        );
+        assert!(backlogs[0].is_hidden);
+        assert_eq!(backlogs[1].work_item_types.len(), 2);
+        assert_eq!(
+            backlogs[0].column_fields[1]
+                .column_field_reference
+                .reference_name,
+            "System.Title"
+        );
+    }
+
+    #[tokio::test]
+    async fn move_to_board_column() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/org/project/team/_apis/work/boards/Stories"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture("board")))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/org/_apis/wit/workitems/7"))
+            .and(body_json(serde_json::json!([
+                {
+                    "op": "add",
+                    "path": "/fields/WEF_6CB513B6E70E43499D9FC94E5BBFB784_Kanban.Column",
+                    "value": "Active"
+                },
+                {
+                    "op": "add",
+                    "path": "/fields/WEF_6CB513B6E70E43499D9FC94E5BBFB784_Kanban.Lane",
+                    "value": "Expedite"
+                }
+            ])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": 7 })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let boards = boards(&server);
+        let board = boards.get("Stories").await.unwrap();
+        assert_eq!(
+            board.column_for_state("Bug", "Active").unwrap().column_type,
+            Some(BoardColumnType::InProgress)
+        );
+        assert_eq!(board.rows[0].name, None);
+        boards
+            .move_to_column("Stories", 7, "Active", Some("Expedite"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn move_to_column_of_board_without_column_field() {
+        let server = MockServer::start().await;
+        let mut board = fixture("board");
+        board["fields"]
+            .as_object_mut()
+            .unwrap()
+            .remove("columnField");
+        Mock::given(method("GET"))
+            .and(path("/org/project/team/_apis/work/boards/Stories"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(board))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": 7 })))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let result = boards(&server)
+            .move_to_column("Stories", 7, "Active", None)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn card_rules_round_trip() {
+        let rules: BoardCardRuleSettings = serde_json::from_value(fixture("card_rules")).unwrap();
+        let fill = &rules.rules["fill"][0];
+        assert!(fill.is_enabled);
+        assert_eq!(fill.clauses[0].operator, "CONTAINS");
+        assert_eq!(fill.settings["background-color"], "#FFDED7");
+
+        let serialized = serde_json::to_value(&rules).unwrap();
+        assert_eq!(serialized["rules"]["fill"][0]["isEnabled"], "true");
+        assert!(serialized["rules"]["tagStyle"][0].get("clauses").is_none());
+    }
+
+    #[tokio::test]
+    async fn reorder_iteration_work_items() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path(
+                "/org/project/team/_apis/work/iterations/i1/workitemsorder",
+            ))
+            .and(body_json(serde_json::json!({
+                "ids": [3, 4],
+                "nextId": 0,
+                "previousId": 2,
+                "parentId": 1
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture("workitemsorder")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let results = boards(&server)
+            .reorder_iteration(
+                "i1",
+                &ReorderOperation::builder(vec![3, 4])
+                    .after(2)
+                    .parent(1)
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(results[0].id, 3);
+        assert!(results[0].order < results[1].order);
+    }
+}
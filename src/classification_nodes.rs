@@ -0,0 +1,270 @@
+//! Area and iteration paths
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/wit/classification%20nodes?view=azure-devops-rest-5.1
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{AzureClient, Future};
+
+/// enum representation of the two classification trees of a project
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeStructureGroup {
+    Areas,
+    Iterations,
+}
+
+impl fmt::Display for TreeStructureGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TreeStructureGroup::Areas => "areas",
+            TreeStructureGroup::Iterations => "iterations",
+        }
+        .fmt(f)
+    }
+}
+
+/// The area or iteration tree of a project
+///
+/// Nodes are addressed by their path relative to the root, e.g. `Release 1/Sprint 2`,
+/// an empty path standing for the root itself.
+pub struct ClassificationNodes {
+    ops: AzureClient,
+    project: String,
+    group: TreeStructureGroup,
+}
+
+impl ClassificationNodes {
+    #[doc(hidden)]
+    pub fn new<P>(ops: AzureClient, project: P, group: TreeStructureGroup) -> Self
+    where
+        P: Into<String>,
+    {
+        ClassificationNodes {
+            ops,
+            project: project.into(),
+            group,
+        }
+    }
+
+    /// Get a node along with `depth` levels of children
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/classificationnodes/{structureGroup}/{path}?$depth={depth}&api-version=5.1
+    pub fn get(&self, path: &str, depth: u32) -> Future<ClassificationNode> {
+        self.ops
+            .get(&format!("{}?$depth={}", self.path(path), depth))
+    }
+
+    /// Create a node under `parent`
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/wit/classificationnodes/{structureGroup}/{path}?api-version=5.1
+    pub fn create(
+        &self,
+        parent: &str,
+        node: &ClassificationNodeOptions,
+    ) -> Future<ClassificationNode> {
+        self.ops.post(&self.path(parent), json!(node))
+    }
+
+    /// Rename a node or change its dates
+    ///
+    /// PATCH https://dev.azure.com/{organization}/{project}/_apis/wit/classificationnodes/{structureGroup}/{path}?api-version=5.1
+    pub fn update(
+        &self,
+        path: &str,
+        node: &ClassificationNodeOptions,
+    ) -> Future<ClassificationNode> {
+        self.ops.patch(&self.path(path), json!(node))
+    }
+
+    /// Move the node with the given id, along with its children, under `parent`
+    pub fn move_node(&self, id: u64, parent: &str) -> Future<ClassificationNode> {
+        let body = serde_json::json!({ "id": id });
+        self.ops.post(&self.path(parent), json!(body))
+    }
+
+    /// Delete a node, moving its work items to the node with id `reclassify_id`
+    ///
+    /// DELETE https://dev.azure.com/{organization}/{project}/_apis/wit/classificationnodes/{structureGroup}/{path}?$reclassifyId={reclassifyId}&api-version=5.1
+    pub fn delete(&self, path: &str, reclassify_id: u64) -> Future<Option<serde_json::Value>> {
+        self.ops.delete(&format!(
+            "{}?$reclassifyId={}",
+            self.path(path),
+            reclassify_id
+        ))
+    }
+
+    fn path(&self, path: &str) -> String {
+        let mut uri = format!(
+            "/{}/{}/_apis/wit/classificationnodes/{}",
+            self.ops.org, self.project, self.group
+        );
+        let path = path.trim_matches(|c| c == '/' || c == '\\');
+        if !path.is_empty() {
+            uri.push('/');
+            uri.push_str(&path.replace('\\', "/"));
+        }
+        uri
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassificationNode {
+    pub id: u64,
+    pub identifier: Option<String>,
+    pub name: String,
+    /// `area` or `iteration`
+    pub structure_type: Option<String>,
+    /// full path, e.g. `\Project\Iteration\Release 1`
+    pub path: Option<String>,
+    #[serde(default)]
+    pub has_children: bool,
+    #[serde(default)]
+    pub children: Vec<ClassificationNode>,
+    pub attributes: Option<ClassificationNodeAttributes>,
+    pub url: Option<String>,
+}
+
+impl ClassificationNode {
+    /// this node and its loaded descendants, parents first
+    pub fn descendants(&self) -> Vec<&ClassificationNode> {
+        let mut nodes = vec![self];
+        let mut index = 0;
+        while index < nodes.len() {
+            let node = nodes[index];
+            nodes.extend(node.children.iter());
+            index += 1;
+        }
+        nodes
+    }
+}
+
+/// Dates of an iteration
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassificationNodeAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ClassificationNodeOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes: Option<ClassificationNodeAttributes>,
+}
+
+impl ClassificationNodeOptions {
+    pub fn builder() -> ClassificationNodeOptionsBuilder {
+        ClassificationNodeOptionsBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct ClassificationNodeOptionsBuilder(ClassificationNodeOptions);
+
+impl ClassificationNodeOptionsBuilder {
+    pub fn name<N>(&mut self, name: N) -> &mut Self
+    where
+        N: Into<String>,
+    {
+        self.0.name = Some(name.into());
+        self
+    }
+
+    /// dates of an iteration
+    pub fn dates(&mut self, start_date: DateTime<Utc>, finish_date: DateTime<Utc>) -> &mut Self {
+        self.0.attributes = Some(ClassificationNodeAttributes {
+            start_date: Some(start_date),
+            finish_date: Some(finish_date),
+        });
+        self
+    }
+
+    pub fn build(&self) -> ClassificationNodeOptions {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn create_and_walk_iterations() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(
+                "/org/project/_apis/wit/classificationnodes/iterations/Release%201",
+            ))
+            .and(body_json(serde_json::json!({
+                "name": "Sprint 1",
+                "attributes": {
+                    "startDate": "2020-05-04T00:00:00Z",
+                    "finishDate": "2020-05-15T00:00:00Z"
+                }
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "id": 12,
+                "name": "Sprint 1",
+                "structureType": "iteration",
+                "hasChildren": false,
+                "path": "\\project\\Iteration\\Release 1\\Sprint 1",
+                "attributes": {
+                    "startDate": "2020-05-04T00:00:00Z",
+                    "finishDate": "2020-05-15T00:00:00Z"
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/org/project/_apis/wit/classificationnodes/iterations",
+            ))
+            .and(query_param("$depth", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 1,
+                "name": "project",
+                "hasChildren": true,
+                "children": [{
+                    "id": 11,
+                    "name": "Release 1",
+                    "hasChildren": true,
+                    "children": [{ "id": 12, "name": "Sprint 1" }]
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let iterations = client.project("project").iterations();
+        let sprint = iterations
+            .create(
+                "/Release 1",
+                &ClassificationNodeOptions::builder()
+                    .name("Sprint 1")
+                    .dates(
+                        Utc.with_ymd_and_hms(2020, 5, 4, 0, 0, 0).unwrap(),
+                        Utc.with_ymd_and_hms(2020, 5, 15, 0, 0, 0).unwrap(),
+                    )
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(sprint.id, 12);
+
+        let root = iterations.get("", 2).await.unwrap();
+        let ids: Vec<u64> = root.descendants().iter().map(|node| node.id).collect();
+        assert_eq!(ids, vec![1, 11, 12]);
+    }
+}
@@ -0,0 +1,636 @@
+//! Git commits, their changes and the diffs between two versions
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/commits?view=azure-devops-rest-5.1
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/diffs?view=azure-devops-rest-5.1
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::{future, stream, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::pull_requests::iterations::{change_types, ChangeType, GitItemRef};
+use crate::pull_requests::GitCommitRef;
+use crate::{AzureClient, Error, Future, Pagination, Stream};
+
+/// Commits of a repository
+pub struct Commits {
+    ops: AzureClient,
+    project: String,
+    repo: String,
+}
+
+impl Commits {
+    #[doc(hidden)]
+    pub fn new<P, R>(ops: AzureClient, project: P, repo: R) -> Self
+    where
+        P: Into<String>,
+        R: Into<String>,
+    {
+        Self {
+            ops,
+            project: project.into(),
+            repo: repo.into(),
+        }
+    }
+
+    /// List a single page of commits
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/commits?api-version=5.1
+    pub fn list(&self, criteria: &CommitSearchCriteria) -> Future<Vec<GitCommitRef>> {
+        let mut query = criteria.serialize();
+        if let Some(top) = criteria.top {
+            query.push(("searchCriteria.$top", top.to_string()));
+        }
+        if let Some(skip) = criteria.skip {
+            query.push(("searchCriteria.$skip", skip.to_string()));
+        }
+        self.ops.get_values(&self.uri("/commits", &query))
+    }
+
+    /// Stream every commit matching `criteria`, requesting pages until exhaustion
+    ///
+    /// `top` sets the page size and `skip` the number of commits to start from
+    pub fn stream(&self, criteria: &CommitSearchCriteria) -> Stream<GitCommitRef> {
+        self.ops.get_stream(
+            &self.uri("/commits", &criteria.serialize()),
+            Pagination::search_criteria(criteria.top, criteria.skip),
+        )
+    }
+
+    /// Get a commit along with up to `change_count` of its changes
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/commits/{commitId}?changeCount={changeCount}&api-version=5.1
+    pub fn get(&self, commit_id: &str, change_count: u32) -> Future<GitCommitRef> {
+        self.ops.get(&self.uri(
+            &format!("/commits/{}", commit_id),
+            &[("changeCount", change_count.to_string())],
+        ))
+    }
+
+    /// Stream every change of a commit
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/commits/{commitId}/changes?top={top}&skip={skip}&api-version=5.1
+    pub fn changes(&self, commit_id: &str) -> Stream<GitChange> {
+        let ops = self.ops.clone();
+        let uri = self.uri(&format!("/commits/{}/changes", commit_id), &[]);
+        let pages = stream::try_unfold(Some(0), move |skip| {
+            let ops = ops.clone();
+            let uri = uri.clone();
+            async move {
+                let skip: u32 = match skip {
+                    Some(skip) => skip,
+                    None => return Ok(None),
+                };
+                let page: GitCommitChanges = ops
+                    .get(&format!("{}?top={}&skip={}", uri, CHANGES_PAGE_SIZE, skip))
+                    .await?;
+                let received = page.changes.len() as u32;
+                let next = if received < CHANGES_PAGE_SIZE {
+                    None
+                } else {
+                    Some(skip + received)
+                };
+                Ok::<_, Error>(Some((page.changes, next)))
+            }
+        });
+        Box::pin(
+            pages
+                .map_ok(|changes| stream::iter(changes.into_iter().map(Ok)))
+                .try_flatten(),
+        )
+    }
+
+    /// Stream every commit matching `criteria`, which unlike `stream` may list `ids`
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/commitsbatch?api-version=5.1
+    pub fn batch(&self, criteria: &CommitSearchCriteria) -> Stream<GitCommitRef> {
+        let body = match serde_json::to_vec(criteria) {
+            Ok(body) => body,
+            Err(err) => return Box::pin(stream::once(future::err(err.into()))),
+        };
+        self.ops.post_stream(
+            &self.uri("/commitsbatch", &[]),
+            body,
+            Pagination::top_skip(criteria.top, criteria.skip),
+        )
+    }
+
+    /// Compare two versions, e.g. to tell how far a branch is ahead of another
+    ///
+    /// Only the first page of changes is returned, see `diff_changes` to list every change
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/diffs/commits?api-version=5.1
+    pub fn diff(
+        &self,
+        base: &GitVersionDescriptor,
+        target: &GitVersionDescriptor,
+    ) -> Future<GitCommitDiffs> {
+        self.ops.get(&self.diff_uri(base, target))
+    }
+
+    /// Stream every change between the `base` and `target` versions
+    pub fn diff_changes(
+        &self,
+        base: &GitVersionDescriptor,
+        target: &GitVersionDescriptor,
+    ) -> Stream<GitChange> {
+        let ops = self.ops.clone();
+        let uri = self.diff_uri(base, target);
+        let pages = stream::try_unfold(Some(Pagination::top_skip(None, None)), move |pagination| {
+            let ops = ops.clone();
+            let uri = uri.clone();
+            async move {
+                let pagination: Pagination = match pagination {
+                    Some(pagination) => pagination,
+                    None => return Ok(None),
+                };
+                let page: GitCommitDiffs = ops.get(&pagination.uri(&uri)).await?;
+                let next = if page.all_changes_included {
+                    None
+                } else {
+                    pagination.next(None, page.changes.len())
+                };
+                Ok::<_, Error>(Some((page.changes, next)))
+            }
+        });
+        Box::pin(
+            pages
+                .map_ok(|changes| stream::iter(changes.into_iter().map(Ok)))
+                .try_flatten(),
+        )
+    }
+
+    fn diff_uri(&self, base: &GitVersionDescriptor, target: &GitVersionDescriptor) -> String {
+        let mut query = Vec::new();
+        base.append_to(&mut query, "baseVersion", "baseVersionType");
+        target.append_to(&mut query, "targetVersion", "targetVersionType");
+        self.uri("/diffs/commits", &query)
+    }
+
+    fn uri(&self, more: &str, query: &[(&str, String)]) -> String {
+        let path = format!(
+            "/{}/{}/_apis/git/repositories/{}{}",
+            self.ops.org, self.project, self.repo, more
+        );
+        if query.is_empty() {
+            return path;
+        }
+        let query: String = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(query)
+            .finish();
+        format!("{}?{}", path, query)
+    }
+}
+
+const CHANGES_PAGE_SIZE: u32 = 100;
+
+/// A change to a file or folder within a commit or a diff
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitChange {
+    pub change_id: Option<u64>,
+    pub item: Option<GitItemRef>,
+    #[serde(rename = "changeType", deserialize_with = "change_types", default)]
+    pub change_types: Vec<ChangeType>,
+    /// path of a renamed file before the rename
+    pub source_server_item: Option<String>,
+    pub original_path: Option<String>,
+    pub url: Option<String>,
+}
+
+impl GitChange {
+    /// path of the changed file or folder
+    pub fn path(&self) -> Option<&str> {
+        self.item.as_ref().and_then(|item| item.path.as_deref())
+    }
+
+    pub fn is_folder(&self) -> bool {
+        self.item.as_ref().is_some_and(GitItemRef::is_tree)
+    }
+}
+
+/// A page of the changes of a commit
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GitCommitChanges {
+    #[serde(default)]
+    changes: Vec<GitChange>,
+}
+
+/// Result of comparing a base and a target version
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitDiffs {
+    /// number of commits in the target version and not in the base version
+    pub ahead_count: Option<u32>,
+    /// number of commits in the base version and not in the target version
+    pub behind_count: Option<u32>,
+    #[serde(default)]
+    pub all_changes_included: bool,
+    /// number of changes by kind, e.g. `Edit`
+    #[serde(default)]
+    pub change_counts: HashMap<String, u32>,
+    #[serde(default)]
+    pub changes: Vec<GitChange>,
+    pub common_commit: Option<String>,
+    pub base_commit: Option<String>,
+    pub target_commit: Option<String>,
+}
+
+/// A branch, tag or commit to read a repository at
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitVersionDescriptor {
+    pub version: String,
+    pub version_type: GitVersionType,
+}
+
+impl GitVersionDescriptor {
+    /// the tip of a branch, e.g. `master`
+    pub fn branch<V>(version: V) -> Self
+    where
+        V: Into<String>,
+    {
+        Self {
+            version: version.into(),
+            version_type: GitVersionType::Branch,
+        }
+    }
+
+    /// a tag, e.g. `v1.0`
+    pub fn tag<V>(version: V) -> Self
+    where
+        V: Into<String>,
+    {
+        Self {
+            version: version.into(),
+            version_type: GitVersionType::Tag,
+        }
+    }
+
+    /// a commit, by its id
+    pub fn commit<V>(version: V) -> Self
+    where
+        V: Into<String>,
+    {
+        Self {
+            version: version.into(),
+            version_type: GitVersionType::Commit,
+        }
+    }
+
+    pub(crate) fn append_to(
+        &self,
+        query: &mut Vec<(&'static str, String)>,
+        version: &'static str,
+        version_type: &'static str,
+    ) {
+        query.push((version, self.version.clone()));
+        query.push((version_type, self.version_type.to_string()));
+    }
+}
+
+/// enum representation of the kinds of Azure Git versions
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GitVersionType {
+    Branch,
+    Tag,
+    Commit,
+}
+
+impl fmt::Display for GitVersionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            GitVersionType::Branch => "branch",
+            GitVersionType::Tag => "tag",
+            GitVersionType::Commit => "commit",
+        }
+        .fmt(f)
+    }
+}
+
+/// Criteria of a commit search, sent as `searchCriteria` parameters by `Commits::stream` and as the
+/// body of `Commits::batch`
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitSearchCriteria {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item_version: Option<GitVersionDescriptor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compare_version: Option<GitVersionDescriptor>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_work_items: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exclude_deletes: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    show_oldest_commits_first: Option<bool>,
+    #[serde(skip)]
+    top: Option<u32>,
+    #[serde(skip)]
+    skip: Option<u32>,
+}
+
+impl CommitSearchCriteria {
+    pub fn builder() -> CommitSearchCriteriaBuilder {
+        CommitSearchCriteriaBuilder::default()
+    }
+
+    /// `searchCriteria` query parameters, paging excepted
+    fn serialize(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if let Some(item_path) = &self.item_path {
+            query.push(("searchCriteria.itemPath", item_path.clone()));
+        }
+        if let Some(author) = &self.author {
+            query.push(("searchCriteria.author", author.clone()));
+        }
+        if let Some(from_date) = &self.from_date {
+            query.push(("searchCriteria.fromDate", from_date.clone()));
+        }
+        if let Some(to_date) = &self.to_date {
+            query.push(("searchCriteria.toDate", to_date.clone()));
+        }
+        if let Some(item_version) = &self.item_version {
+            item_version.append_to(
+                &mut query,
+                "searchCriteria.itemVersion.version",
+                "searchCriteria.itemVersion.versionType",
+            );
+        }
+        if let Some(compare_version) = &self.compare_version {
+            compare_version.append_to(
+                &mut query,
+                "searchCriteria.compareVersion.version",
+                "searchCriteria.compareVersion.versionType",
+            );
+        }
+        if !self.ids.is_empty() {
+            query.push(("searchCriteria.ids", self.ids.join(",")));
+        }
+        if let Some(include_work_items) = self.include_work_items {
+            query.push((
+                "searchCriteria.includeWorkItems",
+                include_work_items.to_string(),
+            ));
+        }
+        if let Some(exclude_deletes) = self.exclude_deletes {
+            query.push(("searchCriteria.excludeDeletes", exclude_deletes.to_string()));
+        }
+        if let Some(oldest_first) = self.show_oldest_commits_first {
+            query.push((
+                "searchCriteria.showOldestCommitsFirst",
+                oldest_first.to_string(),
+            ));
+        }
+        query
+    }
+}
+
+#[derive(Default)]
+pub struct CommitSearchCriteriaBuilder(CommitSearchCriteria);
+
+impl CommitSearchCriteriaBuilder {
+    /// only commits changing files under `item_path`
+    pub fn item_path<P>(&mut self, item_path: P) -> &mut Self
+    where
+        P: Into<String>,
+    {
+        self.0.item_path = Some(item_path.into());
+        self
+    }
+
+    /// only commits whose author name or email contains `author`
+    pub fn author<A>(&mut self, author: A) -> &mut Self
+    where
+        A: Into<String>,
+    {
+        self.0.author = Some(author.into());
+        self
+    }
+
+    pub fn from_date(&mut self, from_date: DateTime<Utc>) -> &mut Self {
+        self.0.from_date = Some(from_date.to_rfc3339_opts(SecondsFormat::Secs, true));
+        self
+    }
+
+    pub fn to_date(&mut self, to_date: DateTime<Utc>) -> &mut Self {
+        self.0.to_date = Some(to_date.to_rfc3339_opts(SecondsFormat::Secs, true));
+        self
+    }
+
+    /// commits reachable from `item_version`, the default branch by default
+    pub fn item_version(&mut self, item_version: GitVersionDescriptor) -> &mut Self {
+        self.0.item_version = Some(item_version);
+        self
+    }
+
+    /// leave out commits reachable from `compare_version`, e.g. the previous release tag
+    pub fn compare_version(&mut self, compare_version: GitVersionDescriptor) -> &mut Self {
+        self.0.compare_version = Some(compare_version);
+        self
+    }
+
+    /// only the commits with these ids
+    pub fn ids<I, S>(&mut self, ids: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.0.ids = ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn include_work_items(&mut self, include_work_items: bool) -> &mut Self {
+        self.0.include_work_items = Some(include_work_items);
+        self
+    }
+
+    pub fn exclude_deletes(&mut self, exclude_deletes: bool) -> &mut Self {
+        self.0.exclude_deletes = Some(exclude_deletes);
+        self
+    }
+
+    pub fn show_oldest_commits_first(&mut self, oldest_first: bool) -> &mut Self {
+        self.0.show_oldest_commits_first = Some(oldest_first);
+        self
+    }
+
+    /// number of commits requested per page
+    pub fn top(&mut self, top: u32) -> &mut Self {
+        self.0.top = Some(top);
+        self
+    }
+
+    /// number of commits to start from
+    pub fn skip(&mut self, skip: u32) -> &mut Self {
+        self.0.skip = Some(skip);
+        self
+    }
+
+    pub fn build(&self) -> CommitSearchCriteria {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    const REPO: &str = "/org/project/_apis/git/repositories/repo";
+
+    #[tokio::test]
+    async fn stream_commits_between_tags() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/commits", REPO)))
+            .and(query_param("searchCriteria.itemVersion.version", "v2.0"))
+            .and(query_param("searchCriteria.itemVersion.versionType", "tag"))
+            .and(query_param("searchCriteria.compareVersion.version", "v1.0"))
+            .and(query_param("searchCriteria.$top", "2"))
+            .and(query_param("searchCriteria.$skip", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 2,
+                "value": [{
+                    "commitId": "c2",
+                    "comment": "Add commits",
+                    "author": { "name": "Jamie Doe", "email": "jamie@example.com" },
+                    "parents": ["c1"],
+                    "changeCounts": { "Add": 1, "Edit": 2 }
+                }, {
+                    "commitId": "c1",
+                    "comment": "Fix refs"
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/commits", REPO)))
+            .and(query_param("searchCriteria.$skip", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 1,
+                "value": [{ "commitId": "c0", "comment": "Bump version" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let commits: Vec<GitCommitRef> = client
+            .repo("project", "repo")
+            .commits()
+            .stream(
+                &CommitSearchCriteria::builder()
+                    .item_version(GitVersionDescriptor::tag("v2.0"))
+                    .compare_version(GitVersionDescriptor::tag("v1.0"))
+                    .top(2)
+                    .build(),
+            )
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<&str> = commits
+            .iter()
+            .map(|commit| commit.commit_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["c2", "c1", "c0"]);
+        assert_eq!(commits[0].parents, vec!["c1"]);
+        assert_eq!(commits[0].change_counts["Edit"], 2);
+    }
+
+    #[tokio::test]
+    async fn batch_commits_by_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/commitsbatch", REPO)))
+            .and(query_param("$top", "100"))
+            .and(query_param("$skip", "0"))
+            .and(body_json(serde_json::json!({
+                "ids": ["c1", "c2"],
+                "includeWorkItems": true
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 2,
+                "value": [{ "commitId": "c1" }, { "commitId": "c2" }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let commits: Vec<GitCommitRef> = client
+            .repo("project", "repo")
+            .commits()
+            .batch(
+                &CommitSearchCriteria::builder()
+                    .ids(vec!["c1", "c2"])
+                    .include_work_items(true)
+                    .build(),
+            )
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(commits.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn diff_between_branches() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/diffs/commits", REPO)))
+            .and(query_param("baseVersion", "master"))
+            .and(query_param("baseVersionType", "branch"))
+            .and(query_param("targetVersion", "feature"))
+            .and(query_param("targetVersionType", "branch"))
+            .and(query_param("$skip", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "aheadCount": 2,
+                "behindCount": 0,
+                "allChangesIncluded": true,
+                "changeCounts": { "Edit": 1, "Rename": 1 },
+                "changes": [{
+                    "item": { "path": "/src/lib.rs", "gitObjectType": "blob" },
+                    "changeType": "edit"
+                }, {
+                    "item": { "path": "/src/commits.rs", "gitObjectType": "blob" },
+                    "sourceServerItem": "/src/history.rs",
+                    "changeType": "rename"
+                }],
+                "commonCommit": "c1",
+                "baseCommit": "c1",
+                "targetCommit": "c3"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let changes: Vec<GitChange> = client
+            .repo("project", "repo")
+            .commits()
+            .diff_changes(
+                &GitVersionDescriptor::branch("master"),
+                &GitVersionDescriptor::branch("feature"),
+            )
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1].path(), Some("/src/commits.rs"));
+        assert_eq!(changes[1].change_types, vec![ChangeType::Rename]);
+    }
+}
@@ -0,0 +1,29 @@
+//! Identities
+use serde::{Deserialize, Serialize};
+
+/// Reference to a user or group
+///
+/// https://docs.microsoft.com/en-us/rest/api/azure/devops/wit/work%20items/get%20work%20item?view=azure-devops-rest-5.1#identityref
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IdentityRef {
+    pub id: Option<String>,
+    pub display_name: Option<String>,
+    pub unique_name: Option<String>,
+    pub url: Option<String>,
+    pub image_url: Option<String>,
+    pub descriptor: Option<String>,
+    pub is_container: Option<bool>,
+    #[serde(rename = "_links", skip_serializing_if = "Option::is_none")]
+    pub links: Option<IdentityLinks>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct IdentityLinks {
+    pub avatar: Option<Href>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Href {
+    pub href: String,
+}
@@ -0,0 +1,394 @@
+//! Git items, trees and blobs: reading the content of a repository
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/items?view=azure-devops-rest-5.1
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/trees?view=azure-devops-rest-5.1
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/blobs?view=azure-devops-rest-5.1
+use std::collections::HashMap;
+use std::fmt;
+
+use bytes::Bytes;
+use futures::TryFutureExt;
+use serde::Deserialize;
+use url::form_urlencoded;
+
+use crate::commits::GitVersionDescriptor;
+use crate::pull_requests::GitCommitRef;
+use crate::{AzureClient, Future, Stream};
+
+/// Files and folders of a repository
+pub struct Items {
+    ops: AzureClient,
+    project: String,
+    repo: String,
+}
+
+impl Items {
+    #[doc(hidden)]
+    pub fn new<P, R>(ops: AzureClient, project: P, repo: R) -> Self
+    where
+        P: Into<String>,
+        R: Into<String>,
+    {
+        Self {
+            ops,
+            project: project.into(),
+            repo: repo.into(),
+        }
+    }
+
+    /// Get the file or folder at `path`
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/items?path={path}&api-version=5.1
+    pub fn get(&self, path: &str, options: &ItemOptions) -> Future<GitItem> {
+        self.ops
+            .get(&self.uri("/items", options, &[("path", path), ("$format", "json")]))
+    }
+
+    /// Text content of the file at `path`
+    pub fn content(&self, path: &str, options: &ItemOptions) -> Future<String> {
+        let mut options = options.clone();
+        options.params.insert("includeContent", true.to_string());
+        Box::pin(
+            self.get(path, &options)
+                .map_ok(|item| item.content.unwrap_or_default()),
+        )
+    }
+
+    /// List the files and folders under `scope_path`, down to `recursion_level`
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/items?scopePath={scopePath}&recursionLevel={recursionLevel}&api-version=5.1
+    pub fn list(&self, options: &ItemOptions) -> Future<Vec<GitItem>> {
+        self.ops
+            .get_values(&self.uri("/items", options, &[("$format", "json")]))
+    }
+
+    /// Stream the raw content of the file at `path`, without buffering it
+    pub fn download(&self, path: &str, options: &ItemOptions) -> Stream<Bytes> {
+        self.ops.download(&self.uri(
+            "/items",
+            options,
+            &[("path", path), ("$format", "octetStream")],
+        ))
+    }
+
+    /// Stream a zip archive of the folder at `scope_path`, without buffering it
+    pub fn download_zip(&self, options: &ItemOptions) -> Stream<Bytes> {
+        self.ops
+            .download(&self.uri("/items", options, &[("$format", "zip")]))
+    }
+
+    /// Get a tree by its object id, along with every subtree if `recursive`
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/trees/{sha1}?recursive={recursive}&api-version=5.1
+    pub fn tree(&self, sha1: &str, recursive: bool) -> Future<GitTreeRef> {
+        self.ops.get(&self.uri(
+            &format!("/trees/{}", sha1),
+            &ItemOptions::default(),
+            &[("recursive", &recursive.to_string())],
+        ))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/blobs/{sha1}?api-version=5.1
+    pub fn blob(&self, sha1: &str) -> Future<GitBlobRef> {
+        self.ops.get(&self.uri(
+            &format!("/blobs/{}", sha1),
+            &ItemOptions::default(),
+            &[("$format", "json")],
+        ))
+    }
+
+    /// Stream the content of a blob, without buffering it
+    pub fn download_blob(&self, sha1: &str) -> Stream<Bytes> {
+        self.ops.download(&self.uri(
+            &format!("/blobs/{}", sha1),
+            &ItemOptions::default(),
+            &[("$format", "octetStream")],
+        ))
+    }
+
+    fn uri(&self, more: &str, options: &ItemOptions, params: &[(&str, &str)]) -> String {
+        let query: String = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .extend_pairs(&options.params)
+            .finish();
+        format!(
+            "/{}/{}/_apis/git/repositories/{}{}?{}",
+            self.ops.org, self.project, self.repo, more, query
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitItem {
+    pub object_id: Option<String>,
+    pub original_object_id: Option<String>,
+    /// `blob` for files and `tree` for folders
+    pub git_object_type: Option<String>,
+    pub commit_id: Option<String>,
+    pub path: String,
+    #[serde(default)]
+    pub is_folder: bool,
+    /// content of the file, see `ItemOptionsBuilder::include_content`
+    pub content: Option<String>,
+    pub content_metadata: Option<FileContentMetadata>,
+    pub latest_processed_change: Option<GitCommitRef>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileContentMetadata {
+    pub content_type: Option<String>,
+    pub encoding: Option<i32>,
+    pub extension: Option<String>,
+    pub file_name: Option<String>,
+    #[serde(default)]
+    pub is_binary: bool,
+    #[serde(default)]
+    pub is_image: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitTreeRef {
+    pub object_id: String,
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub tree_entries: Vec<GitTreeEntryRef>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitTreeEntryRef {
+    pub object_id: String,
+    /// `blob` for files and `tree` for folders
+    pub git_object_type: Option<String>,
+    /// file mode, e.g. `100644`
+    pub mode: Option<String>,
+    /// path relative to the tree listed
+    pub relative_path: String,
+    pub size: Option<u64>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBlobRef {
+    pub object_id: String,
+    pub size: Option<u64>,
+    pub url: Option<String>,
+}
+
+/// enum representation of how deep items are listed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecursionLevel {
+    None,
+    OneLevel,
+    OneLevelPlusNestedEmptyFolders,
+    Full,
+}
+
+impl fmt::Display for RecursionLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RecursionLevel::None => "none",
+            RecursionLevel::OneLevel => "oneLevel",
+            RecursionLevel::OneLevelPlusNestedEmptyFolders => "oneLevelPlusNestedEmptyFolders",
+            RecursionLevel::Full => "full",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ItemOptions {
+    params: HashMap<&'static str, String>,
+}
+
+impl ItemOptions {
+    pub fn builder() -> ItemOptionsBuilder {
+        ItemOptionsBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct ItemOptionsBuilder(ItemOptions);
+
+impl ItemOptionsBuilder {
+    /// folder whose items are listed
+    pub fn scope_path<P>(&mut self, scope_path: P) -> &mut Self
+    where
+        P: Into<String>,
+    {
+        self.0.params.insert("scopePath", scope_path.into());
+        self
+    }
+
+    pub fn recursion_level(&mut self, recursion_level: RecursionLevel) -> &mut Self {
+        self.0
+            .params
+            .insert("recursionLevel", recursion_level.to_string());
+        self
+    }
+
+    /// branch, tag or commit to read, the default branch by default
+    pub fn version(&mut self, version: GitVersionDescriptor) -> &mut Self {
+        self.0
+            .params
+            .insert("versionDescriptor.version", version.version);
+        self.0.params.insert(
+            "versionDescriptor.versionType",
+            version.version_type.to_string(),
+        );
+        self
+    }
+
+    /// also return the content of files, as text
+    pub fn include_content(&mut self, include_content: bool) -> &mut Self {
+        self.0
+            .params
+            .insert("includeContent", include_content.to_string());
+        self
+    }
+
+    pub fn include_content_metadata(&mut self, include_content_metadata: bool) -> &mut Self {
+        self.0.params.insert(
+            "includeContentMetadata",
+            include_content_metadata.to_string(),
+        );
+        self
+    }
+
+    /// also return the last commit changing each item
+    pub fn latest_processed_change(&mut self, latest_processed_change: bool) -> &mut Self {
+        self.0
+            .params
+            .insert("latestProcessedChange", latest_processed_change.to_string());
+        self
+    }
+
+    pub fn build(&self) -> ItemOptions {
+        ItemOptions {
+            params: self.0.params.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    const REPO: &str = "/org/project/_apis/git/repositories/repo";
+
+    #[tokio::test]
+    async fn read_file_content_at_tag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/items", REPO)))
+            .and(query_param("path", "/azure-pipelines.yml"))
+            .and(query_param("includeContent", "true"))
+            .and(query_param("versionDescriptor.version", "v1.0"))
+            .and(query_param("versionDescriptor.versionType", "tag"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "objectId": "b1",
+                "gitObjectType": "blob",
+                "commitId": "c1",
+                "path": "/azure-pipelines.yml",
+                "content": "trigger:\n- master\n"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/items", REPO)))
+            .and(query_param("path", "/azure-pipelines.yml"))
+            .and(query_param("$format", "octetStream"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_bytes(b"trigger:\n- master\n".to_vec()),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let items = client.repo("project", "repo").items();
+        let content = items
+            .content(
+                "/azure-pipelines.yml",
+                &ItemOptions::builder()
+                    .version(GitVersionDescriptor::tag("v1.0"))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(content, "trigger:\n- master\n");
+
+        let chunks: Vec<Bytes> = items
+            .download("/azure-pipelines.yml", &ItemOptions::default())
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks.concat(), b"trigger:\n- master\n".to_vec());
+    }
+
+    #[tokio::test]
+    async fn list_items_and_tree() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/items", REPO)))
+            .and(query_param("scopePath", "/src"))
+            .and(query_param("recursionLevel", "oneLevel"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 2,
+                "value": [{
+                    "objectId": "t1",
+                    "gitObjectType": "tree",
+                    "path": "/src",
+                    "isFolder": true
+                }, {
+                    "objectId": "b1",
+                    "gitObjectType": "blob",
+                    "path": "/src/lib.rs"
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/trees/t1", REPO)))
+            .and(query_param("recursive", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "objectId": "t1",
+                "treeEntries": [{
+                    "objectId": "b1",
+                    "gitObjectType": "blob",
+                    "mode": "100644",
+                    "relativePath": "lib.rs",
+                    "size": 42
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let items = client.repo("project", "repo").items();
+        let listed = items
+            .list(
+                &ItemOptions::builder()
+                    .scope_path("/src")
+                    .recursion_level(RecursionLevel::OneLevel)
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert!(listed[0].is_folder);
+        assert_eq!(listed[1].path, "/src/lib.rs");
+
+        let tree = items.tree("t1", true).await.unwrap();
+        assert_eq!(tree.tree_entries[0].relative_path, "lib.rs");
+        assert_eq!(tree.tree_entries[0].size, Some(42));
+    }
+}
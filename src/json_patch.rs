@@ -0,0 +1,223 @@
+//! JSON Patch documents
+//!
+//! https://tools.ietf.org/html/rfc6902
+use serde::Serialize;
+use serde_json::Value;
+
+/// enum representation of JSON Patch operations
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatchOp {
+    Add,
+    Remove,
+    Replace,
+    Move,
+    Copy,
+    Test,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PatchOperation {
+    pub op: PatchOp,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+/// A list of operations, sent as `application/json-patch+json`
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct JsonPatch {
+    pub operations: Vec<PatchOperation>,
+}
+
+impl JsonPatch {
+    pub fn builder() -> JsonPatchBuilder {
+        JsonPatchBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct JsonPatchBuilder(JsonPatch);
+
+impl JsonPatchBuilder {
+    /// append an arbitrary operation
+    pub fn operation(&mut self, operation: PatchOperation) -> &mut Self {
+        self.0.operations.push(operation);
+        self
+    }
+
+    pub fn add<P, V>(&mut self, path: P, value: V) -> &mut Self
+    where
+        P: Into<String>,
+        V: Into<Value>,
+    {
+        self.value(PatchOp::Add, path.into(), value.into())
+    }
+
+    pub fn replace<P, V>(&mut self, path: P, value: V) -> &mut Self
+    where
+        P: Into<String>,
+        V: Into<Value>,
+    {
+        self.value(PatchOp::Replace, path.into(), value.into())
+    }
+
+    pub fn test<P, V>(&mut self, path: P, value: V) -> &mut Self
+    where
+        P: Into<String>,
+        V: Into<Value>,
+    {
+        self.value(PatchOp::Test, path.into(), value.into())
+    }
+
+    pub fn remove<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<String>,
+    {
+        self.operation(PatchOperation {
+            op: PatchOp::Remove,
+            path: path.into(),
+            from: None,
+            value: None,
+        })
+    }
+
+    /// set a work item field, e.g. `System.Title`
+    pub fn add_field<F, V>(&mut self, field: F, value: V) -> &mut Self
+    where
+        F: AsRef<str>,
+        V: Into<Value>,
+    {
+        self.add(field_path(field.as_ref()), value)
+    }
+
+    pub fn replace_field<F, V>(&mut self, field: F, value: V) -> &mut Self
+    where
+        F: AsRef<str>,
+        V: Into<Value>,
+    {
+        self.replace(field_path(field.as_ref()), value)
+    }
+
+    pub fn remove_field<F>(&mut self, field: F) -> &mut Self
+    where
+        F: AsRef<str>,
+    {
+        self.remove(field_path(field.as_ref()))
+    }
+
+    /// fail the whole patch unless the field has the given value
+    pub fn test_field<F, V>(&mut self, field: F, value: V) -> &mut Self
+    where
+        F: AsRef<str>,
+        V: Into<Value>,
+    {
+        self.test(field_path(field.as_ref()), value)
+    }
+
+    /// fail the whole patch unless the work item is at revision `rev`, guarding against concurrent updates
+    pub fn test_rev(&mut self, rev: u64) -> &mut Self {
+        self.test("/rev", rev)
+    }
+
+    /// link the work item to `url`, e.g. `System.LinkTypes.Hierarchy-Reverse` for a parent
+    pub fn add_relation<R, U>(&mut self, rel: R, url: U, comment: Option<&str>) -> &mut Self
+    where
+        R: Into<String>,
+        U: Into<String>,
+    {
+        let mut relation = serde_json::json!({
+            "rel": rel.into(),
+            "url": url.into(),
+        });
+        if let Some(comment) = comment {
+            relation["attributes"] = serde_json::json!({ "comment": comment });
+        }
+        self.add("/relations/-", relation)
+    }
+
+    /// link the work item to a commit, pull request or build, see `work_items::Artifact`
+    pub fn add_artifact_link<U, N>(&mut self, uri: U, name: N, comment: Option<&str>) -> &mut Self
+    where
+        U: Into<String>,
+        N: Into<String>,
+    {
+        let mut attributes = serde_json::json!({ "name": name.into() });
+        if let Some(comment) = comment {
+            attributes["comment"] = comment.into();
+        }
+        self.add(
+            "/relations/-",
+            serde_json::json!({
+                "rel": "ArtifactLink",
+                "url": uri.into(),
+                "attributes": attributes,
+            }),
+        )
+    }
+
+    /// remove the relation at `index` in the work item's `relations`
+    pub fn remove_relation(&mut self, index: usize) -> &mut Self {
+        self.remove(format!("/relations/{}", index))
+    }
+
+    pub fn build(&self) -> JsonPatch {
+        self.0.clone()
+    }
+
+    fn value(&mut self, op: PatchOp, path: String, value: Value) -> &mut Self {
+        self.operation(PatchOperation {
+            op,
+            path,
+            from: None,
+            value: Some(value),
+        })
+    }
+}
+
+fn field_path(field: &str) -> String {
+    format!("/fields/{}", field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_work_item_patch() {
+        let patch = JsonPatch::builder()
+            .test_rev(3)
+            .add_field("System.Title", "Crash on startup")
+            .replace_field("Microsoft.VSTS.Common.Priority", 1)
+            .remove_field("System.Tags")
+            .add_relation(
+                "System.LinkTypes.Hierarchy-Reverse",
+                "https://dev.azure.com/org/_apis/wit/workItems/1",
+                Some("parent"),
+            )
+            .remove_relation(2)
+            .build();
+        assert_eq!(
+            serde_json::to_value(&patch).unwrap(),
+            serde_json::json!([
+                { "op": "test", "path": "/rev", "value": 3 },
+                { "op": "add", "path": "/fields/System.Title", "value": "Crash on startup" },
+                { "op": "replace", "path": "/fields/Microsoft.VSTS.Common.Priority", "value": 1 },
+                { "op": "remove", "path": "/fields/System.Tags" },
+                {
+                    "op": "add",
+                    "path": "/relations/-",
+                    "value": {
+                        "rel": "System.LinkTypes.Hierarchy-Reverse",
+                        "url": "https://dev.azure.com/org/_apis/wit/workItems/1",
+                        "attributes": { "comment": "parent" }
+                    }
+                },
+                { "op": "remove", "path": "/relations/2" }
+            ])
+        );
+    }
+}
@@ -1,14 +1,15 @@
 #![allow(missing_docs)] // todo: make this a deny eventually
 
+use std::collections::HashMap;
 use std::fmt;
 use std::pin::Pin;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use futures::{future, prelude::*, Future as StdFuture, Stream as StdStream};
+use bytes::Bytes;
+use futures::{future, prelude::*, stream, Future as StdFuture, Stream as StdStream};
 #[cfg(feature = "httpcache")]
 use http::header::IF_NONE_MATCH;
 use http::header::{HeaderMap, HeaderValue};
-use http::header::{ACCEPT, AUTHORIZATION, ETAG, LINK, USER_AGENT};
+use http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ETAG, LINK, USER_AGENT};
 use http::{Method, StatusCode};
 #[cfg(feature = "httpcache")]
 use hyperx::header::LinkValue;
@@ -18,25 +19,60 @@ use mime::Mime;
 use reqwest::Url;
 use reqwest::{Body, Client};
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::form_urlencoded;
 
 #[doc(hidden)] // public for doc testing and integration testing only
 #[cfg(feature = "httpcache")]
 pub mod http_cache;
 #[macro_use]
 mod macros; // expose json! macro to child modules
+pub mod boards;
+pub mod classification_nodes;
+pub mod commits;
 pub mod errors;
+pub mod identity;
+pub mod items;
+pub mod json_patch;
+pub mod policies;
+pub mod processes;
 pub mod projects;
 pub mod repository;
 pub mod pull_requests;
+pub mod pushes;
+pub mod rate_limit;
+pub mod refs;
+pub mod retry;
+pub mod teams;
+pub mod wiql;
+pub mod work_item_comments;
+pub mod work_item_types;
 pub mod work_items;
 
 pub use crate::errors::{Error, ErrorKind, Result};
 #[cfg(feature = "httpcache")]
 pub use crate::http_cache::{BoxedHttpCache, HttpCache};
-
-use crate::projects::{Project, Projects};
-use crate::repository::{Repositories, Repository};
-use crate::work_items::{WorkItem, WorkItems};
+pub use crate::identity::IdentityRef;
+pub use crate::json_patch::{JsonPatch, JsonPatchBuilder, PatchOp, PatchOperation};
+pub use crate::rate_limit::RateLimitStatus;
+pub use crate::retry::{RetryPolicy, RetryPolicyBuilder};
+pub use crate::wiql::{Wiql, WiqlOptions, WorkItemQueryResult};
+
+use crate::errors::ClientError;
+use crate::processes::Processes;
+use crate::projects::{Project, ProjectOptions, Projects};
+use crate::pull_requests::{GitPullRequest, PullListOptions};
+use crate::rate_limit::RateLimiter;
+use crate::repository::{RepoListOptions, ReposValue, Repositories, Repository};
+use crate::retry::Failure;
+use crate::wiql::QueryHierarchyItem;
+use crate::work_item_comments::{
+    Comment, CommentList, CommentListOptions, CommentReaction, CommentReactionType, CommentText,
+};
+use crate::work_items::{
+    Artifact, AttachmentReference, WorkItem, WorkItemBatchOptions, WorkItemDelete, WorkItemExpand,
+    WorkItemRelationType, WorkItemUpdate, WorkItemUpdateOptions,
+};
 
 const DEFAULT_HOST: &str = "https://dev.azure.com";
 /// A type alias for `Futures` that may return `azure_rs::Errors`
@@ -45,6 +81,9 @@ pub type Future<T> = Pin<Box<dyn StdFuture<Output = Result<T>> + Send>>;
 /// A type alias for `Streams` that may result in `azure_rs::Errors`
 pub type Stream<T> = Pin<Box<dyn StdStream<Item = Result<T>> + Send>>;
 
+/// A single request attempt, see `retry::Failure`
+type Attempt<T> = Pin<Box<dyn StdFuture<Output = std::result::Result<T, Failure>> + Send>>;
+
 /// Rate limiting
 ///
 /// https://docs.microsoft.com/en-us/azure/devops/integrate/concepts/rate-limits?view=azure-devops#api-client-experience
@@ -52,6 +91,17 @@ const X_RATELIMIT_LIMIT: &str = "x-ratelimit-limit";
 const X_RATELIMIT_REMAINING: &str = "x-ratelimit-remaining";
 const X_RATELIMIT_RESET: &str = "x-ratelimit-reset";
 
+/// Continuation token of the next page of a list response
+///
+/// https://docs.microsoft.com/en-us/rest/api/azure/devops/core/projects/list?view=azure-devops-rest-5.1
+const X_MS_CONTINUATIONTOKEN: &str = "x-ms-continuationtoken";
+
+/// Page size used by streams over `$top`/`$skip` paginated endpoints
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// Maximum number of work items the batch API returns at once
+const WORK_ITEMS_BATCH_SIZE: usize = 200;
+
 #[derive(Clone, Copy)]
 pub enum MediaType {
     /// Return json (the default)
@@ -127,6 +177,18 @@ impl fmt::Display for ApiVersion {
     }
 }
 
+impl ApiVersion {
+    /// the version parameter of preview resources, e.g. `api-version=5.1-preview.3`
+    fn preview(self, revision: u8) -> String {
+        let version = match self {
+            ApiVersion::V5_1 => "5.1",
+            ApiVersion::V5_0 => "5.0",
+            ApiVersion::V7_1Preview => "7.1",
+        };
+        format!("api-version={}-preview.{}", version, revision)
+    }
+}
+
 impl Default for ApiVersion {
     fn default() -> ApiVersion {
         ApiVersion::V5_1
@@ -157,6 +219,8 @@ pub struct AzureClient {
     #[cfg(feature = "httpcache")]
     http_cache: BoxedHttpCache,
     api_version: ApiVersion,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
 }
 
 impl AzureClient {
@@ -217,6 +281,8 @@ impl AzureClient {
             credentials: credentials.into(),
             http_cache,
             api_version: ApiVersion::default(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
         }
     }
 
@@ -235,6 +301,8 @@ impl AzureClient {
             client: http,
             credentials: credentials.into(),
             api_version: ApiVersion::default(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
         }
     }
 
@@ -251,6 +319,23 @@ impl AzureClient {
         self.api_version = version.into();
     }
 
+    /// Replace the policy used to retry throttled and failed requests
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Delay requests until the rate limit resets once no more than `threshold` TSTUs remain
+    ///
+    /// Defaults to 0, only holding requests back once the limit is exhausted
+    pub fn set_rate_limit_threshold(&mut self, threshold: u32) {
+        self.rate_limiter.set_threshold(threshold);
+    }
+
+    /// Resource consumption last reported by Azure, shared by all clones of this client
+    pub fn rate_limit_status(&self) -> RateLimitStatus {
+        self.rate_limiter.status()
+    }
+
     pub fn set_host<H>(&mut self, host: H)
     where
     H: Into<String>,
@@ -269,13 +354,581 @@ impl AzureClient {
         Ok(self.get(&format!("/{}/_apis/wit/workItems/{}", self.org, id)).await?)
     }
 
+    /// Get a work item deserialized into a user provided struct
+    pub async fn work_item_as<T>(&self, id: usize) -> Result<T>
+    where
+        T: DeserializeOwned + 'static + Send,
+    {
+        self.get(&format!("/{}/_apis/wit/workItems/{}", self.org, id))
+            .await
+    }
+
+    /// Create a work item of the given type from the fields and relations of `ops`
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/wit/workitems/${type}?api-version=5.1
+    pub async fn create_work_item<P, T>(
+        &self,
+        project: P,
+        work_item_type: T,
+        ops: &JsonPatch,
+        options: &WorkItemUpdateOptions,
+    ) -> Result<WorkItem>
+    where
+        P: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let mut uri = vec![format!(
+            "/{}/{}/_apis/wit/workitems/${}",
+            self.org,
+            project.as_ref(),
+            work_item_type.as_ref()
+        )];
+        if let Some(query) = options.serialize() {
+            uri.push(query);
+        }
+        self.post_media(
+            &uri.join("?"),
+            serde_json::to_vec(ops)?,
+            MediaType::JsonPatch,
+            AuthenticationConstraint::Unconstrained,
+        )
+        .await
+    }
+
+    /// Apply `ops` to a work item
+    ///
+    /// PATCH https://dev.azure.com/{organization}/_apis/wit/workitems/{id}?api-version=5.1
+    pub async fn update_work_item(
+        &self,
+        id: usize,
+        ops: &JsonPatch,
+        options: &WorkItemUpdateOptions,
+    ) -> Result<WorkItem> {
+        let mut uri = vec![format!("/{}/_apis/wit/workitems/{}", self.org, id)];
+        if let Some(query) = options.serialize() {
+            uri.push(query);
+        }
+        self.patch_media(
+            &uri.join("?"),
+            serde_json::to_vec(ops)?,
+            MediaType::JsonPatch,
+        )
+        .await
+    }
+
+    /// Move a work item to the recycle bin, or delete it permanently when `destroy` is set
+    ///
+    /// DELETE https://dev.azure.com/{organization}/_apis/wit/workitems/{id}?destroy={destroy}&api-version=5.1
+    pub async fn delete_work_item(
+        &self,
+        id: usize,
+        destroy: bool,
+    ) -> Result<Option<WorkItemDelete>> {
+        self.delete(&format!(
+            "/{}/_apis/wit/workitems/{}?destroy={}",
+            self.org, id, destroy
+        ))
+        .await
+    }
+
+    /// Get a work item along with its relations, links or both
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/wit/workitems/{id}?$expand={expand}&api-version=5.1
+    pub async fn work_item_expanded(&self, id: usize, expand: WorkItemExpand) -> Result<WorkItem> {
+        self.get(&format!(
+            "/{}/_apis/wit/workItems/{}?$expand={}",
+            self.org, id, expand
+        ))
+        .await
+    }
+
+    /// Get the children of a work item, with their relations expanded so the hierarchy can be walked further down
+    pub async fn work_item_children(&self, id: usize) -> Result<Vec<WorkItem>> {
+        let parent = self
+            .work_item_expanded(id, WorkItemExpand::Relations)
+            .await?;
+        let options = WorkItemBatchOptions::builder()
+            .expand(WorkItemExpand::Relations)
+            .build();
+        self.work_items_batch(&parent.child_ids(), &options).await
+    }
+
+    /// Link a work item to another one, e.g. with `WorkItemRelationType::Parent` to set its parent
+    pub async fn link_work_items(
+        &self,
+        id: usize,
+        relation_type: &WorkItemRelationType,
+        target: usize,
+        comment: Option<&str>,
+    ) -> Result<WorkItem> {
+        let ops = JsonPatch::builder()
+            .add_relation(relation_type.rel(), self.work_item_url(target), comment)
+            .build();
+        self.update_work_item(id, &ops, &expand_relations()).await
+    }
+
+    /// Remove the links of the given type from a work item to another one
+    pub async fn unlink_work_items(
+        &self,
+        id: usize,
+        relation_type: &WorkItemRelationType,
+        target: usize,
+    ) -> Result<WorkItem> {
+        let work_item = self
+            .work_item_expanded(id, WorkItemExpand::Relations)
+            .await?;
+        let indices: Vec<usize> = work_item
+            .relations
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter(|(_, relation)| {
+                relation.relation_type() == *relation_type
+                    && relation.work_item_id() == Some(target)
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if indices.is_empty() {
+            return Ok(work_item);
+        }
+        let mut ops = JsonPatch::builder();
+        if let Some(rev) = work_item.rev {
+            ops.test_rev(rev);
+        }
+        // operations apply in order, so remove the last relations first
+        for index in indices.into_iter().rev() {
+            ops.remove_relation(index);
+        }
+        self.update_work_item(id, &ops.build(), &expand_relations())
+            .await
+    }
+
+    /// Link a work item to a commit, pull request or build
+    pub async fn link_artifact(
+        &self,
+        id: usize,
+        artifact: &Artifact,
+        comment: Option<&str>,
+    ) -> Result<WorkItem> {
+        let ops = JsonPatch::builder()
+            .add_artifact_link(artifact.uri(), artifact.link_name(), comment)
+            .build();
+        self.update_work_item(id, &ops, &expand_relations()).await
+    }
+
+    /// Upload a file to be attached to work items with `attach`
+    ///
+    /// The body is streamed as is, so it can be built from a file or any other stream with `reqwest::Body::wrap_stream`.
+    /// It is only sent once, failed uploads aren't retried.
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/wit/attachments?fileName={fileName}&api-version=5.1
+    pub async fn upload_attachment<P, F, B>(
+        &self,
+        project: P,
+        file_name: F,
+        body: B,
+    ) -> Result<AttachmentReference>
+    where
+        P: AsRef<str>,
+        F: AsRef<str>,
+        B: Into<Body>,
+    {
+        let query = form_urlencoded::Serializer::new(String::new())
+            .append_pair("fileName", file_name.as_ref())
+            .finish();
+        let response = self
+            .request_raw(
+                Method::POST,
+                &format!(
+                    "/{}/{}/_apis/wit/attachments?{}",
+                    self.org,
+                    project.as_ref(),
+                    query
+                ),
+                Some(body.into()),
+                "application/octet-stream",
+            )
+            .await?;
+        Ok(serde_json::from_slice(&response.bytes().await?)?)
+    }
+
+    /// Download an attachment, streaming its content instead of buffering it
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/wit/attachments/{id}?download=true&api-version=5.1
+    pub fn attachment<I>(&self, id: I) -> Stream<Bytes>
+    where
+        I: AsRef<str>,
+    {
+        self.download(&format!(
+            "/{}/_apis/wit/attachments/{}?download=true",
+            self.org,
+            id.as_ref()
+        ))
+    }
+
+    /// Attach an uploaded file to a work item
+    pub async fn attach(
+        &self,
+        id: usize,
+        attachment: &AttachmentReference,
+        comment: Option<&str>,
+    ) -> Result<WorkItem> {
+        let ops = JsonPatch::builder()
+            .add_relation(
+                WorkItemRelationType::AttachedFile.rel(),
+                attachment.url.clone(),
+                comment,
+            )
+            .build();
+        self.update_work_item(id, &ops, &expand_relations()).await
+    }
+
+    /// Stream every revision of a work item, oldest first
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/wit/workItems/{id}/revisions?$expand={expand}&api-version=5.1
+    pub fn work_item_revisions(&self, id: usize, expand: WorkItemExpand) -> Stream<WorkItem> {
+        self.get_stream(
+            &format!(
+                "/{}/_apis/wit/workItems/{}/revisions?$expand={}",
+                self.org, id, expand
+            ),
+            Pagination::top_skip(None, None),
+        )
+    }
+
+    /// Stream the updates of a work item, oldest first, each with the fields and relations it changed
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/wit/workItems/{id}/updates?api-version=5.1
+    pub fn work_item_updates(&self, id: usize) -> Stream<WorkItemUpdate> {
+        self.get_stream(
+            &format!("/{}/_apis/wit/workItems/{}/updates", self.org, id),
+            Pagination::top_skip(None, None),
+        )
+    }
+
+    /// Stream the comments of a work item
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/workItems/{workItemId}/comments?api-version=5.1-preview.3
+    pub fn work_item_comments<P>(
+        &self,
+        project: P,
+        id: usize,
+        options: &CommentListOptions,
+    ) -> Stream<Comment>
+    where
+        P: AsRef<str>,
+    {
+        let mut uri = self.comments_uri(project.as_ref(), id, "");
+        if let Some(query) = options.serialize() {
+            uri.push('&');
+            uri.push_str(&query);
+        }
+        let instance = self.clone();
+        let pages = stream::try_unfold(
+            Some(Pagination::ContinuationToken(None)),
+            move |pagination| {
+                let instance = instance.clone();
+                let uri = uri.clone();
+                async move {
+                    let pagination: Pagination = match pagination {
+                        Some(pagination) => pagination,
+                        None => return Ok(None),
+                    };
+                    // unlike other lists, comments carry the continuation token in the body
+                    let page: CommentList = instance.get(&pagination.uri(&uri)).await?;
+                    let received = page.comments.len();
+                    let next = pagination.next(page.continuation_token, received);
+                    Ok::<_, Error>(Some((page.comments, next)))
+                }
+            },
+        );
+        Box::pin(
+            pages
+                .map_ok(|comments| stream::iter(comments.into_iter().map(Ok)))
+                .try_flatten(),
+        )
+    }
+
+    /// Comment on a work item
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/wit/workItems/{workItemId}/comments?api-version=5.1-preview.3
+    pub async fn add_work_item_comment<P>(
+        &self,
+        project: P,
+        id: usize,
+        text: &str,
+    ) -> Result<Comment>
+    where
+        P: AsRef<str>,
+    {
+        self.post(
+            &self.comments_uri(project.as_ref(), id, ""),
+            serde_json::to_vec(&CommentText { text })?,
+        )
+        .await
+    }
+
+    /// Replace the text of a comment
+    ///
+    /// PATCH https://dev.azure.com/{organization}/{project}/_apis/wit/workItems/{workItemId}/comments/{commentId}?api-version=5.1-preview.3
+    pub async fn edit_work_item_comment<P>(
+        &self,
+        project: P,
+        id: usize,
+        comment_id: u64,
+        text: &str,
+    ) -> Result<Comment>
+    where
+        P: AsRef<str>,
+    {
+        self.patch(
+            &self.comments_uri(project.as_ref(), id, &format!("/{}", comment_id)),
+            serde_json::to_vec(&CommentText { text })?,
+        )
+        .await
+    }
+
+    /// DELETE https://dev.azure.com/{organization}/{project}/_apis/wit/workItems/{workItemId}/comments/{commentId}?api-version=5.1-preview.3
+    pub async fn delete_work_item_comment<P>(
+        &self,
+        project: P,
+        id: usize,
+        comment_id: u64,
+    ) -> Result<()>
+    where
+        P: AsRef<str>,
+    {
+        self.delete::<Option<serde_json::Value>>(&self.comments_uri(
+            project.as_ref(),
+            id,
+            &format!("/{}", comment_id),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/workItems/{workItemId}/comments/{commentId}/reactions?api-version=5.1-preview.1
+    pub async fn work_item_comment_reactions<P>(
+        &self,
+        project: P,
+        id: usize,
+        comment_id: u64,
+    ) -> Result<Vec<CommentReaction>>
+    where
+        P: AsRef<str>,
+    {
+        let uri = self.reactions_uri(project.as_ref(), id, comment_id, "");
+        self.get_values(&uri).await
+    }
+
+    /// React to a comment as the authenticated user
+    ///
+    /// PUT https://dev.azure.com/{organization}/{project}/_apis/wit/workItems/{workItemId}/comments/{commentId}/reactions/{reactionType}?api-version=5.1-preview.1
+    pub async fn add_work_item_comment_reaction<P>(
+        &self,
+        project: P,
+        id: usize,
+        comment_id: u64,
+        reaction: CommentReactionType,
+    ) -> Result<CommentReaction>
+    where
+        P: AsRef<str>,
+    {
+        let uri = self.reactions_uri(project.as_ref(), id, comment_id, &format!("/{}", reaction));
+        self.put(&uri, Vec::new()).await
+    }
+
+    /// Withdraw a reaction of the authenticated user
+    ///
+    /// DELETE https://dev.azure.com/{organization}/{project}/_apis/wit/workItems/{workItemId}/comments/{commentId}/reactions/{reactionType}?api-version=5.1-preview.1
+    pub async fn remove_work_item_comment_reaction<P>(
+        &self,
+        project: P,
+        id: usize,
+        comment_id: u64,
+        reaction: CommentReactionType,
+    ) -> Result<CommentReaction>
+    where
+        P: AsRef<str>,
+    {
+        let uri = self.reactions_uri(project.as_ref(), id, comment_id, &format!("/{}", reaction));
+        self.delete(&uri).await
+    }
+
+    fn comments_uri(&self, project: &str, id: usize, more: &str) -> String {
+        format!(
+            "/{}/{}/_apis/wit/workItems/{}/comments{}?{}",
+            self.org,
+            project,
+            id,
+            more,
+            self.api_version.preview(3)
+        )
+    }
+
+    fn reactions_uri(&self, project: &str, id: usize, comment_id: u64, more: &str) -> String {
+        format!(
+            "/{}/{}/_apis/wit/workItems/{}/comments/{}/reactions{}?{}",
+            self.org,
+            project,
+            id,
+            comment_id,
+            more,
+            self.api_version.preview(1)
+        )
+    }
+
+    /// url of a work item, as used by work item links
+    fn work_item_url(&self, id: usize) -> String {
+        format!("{}/{}/_apis/wit/workItems/{}", self.host, self.org, id)
+    }
+
     pub async fn query_work_items(&self, query: &str) -> Result<Vec<WorkItem>> {
-        let work_items_refs: WorkItems = self.post(&format!("/{}/_apis/wit/wiql", self.org), query.as_bytes().into()).await?;
-        let mut work_items = Vec::new();
-        for work_item in work_items_refs.work_items {
-            work_items.push(self.work_item(work_item.id).await?);
+        self.query_work_items_with(query, &WorkItemBatchOptions::default())
+            .await
+    }
+
+    /// Run a WIQL query and fetch the resulting work items in batches, in the order of the query
+    pub async fn query_work_items_with(
+        &self,
+        query: &str,
+        options: &WorkItemBatchOptions,
+    ) -> Result<Vec<WorkItem>> {
+        let work_items_refs: WorkItemQueryResult = self.post(&format!("/{}/_apis/wit/wiql", self.org), query.as_bytes().into()).await?;
+        self.work_items_of(&work_items_refs, options).await
+    }
+
+    /// Fetch the work items resulting from a query in batches, in the order of the query
+    pub async fn work_items_of(
+        &self,
+        result: &WorkItemQueryResult,
+        options: &WorkItemBatchOptions,
+    ) -> Result<Vec<WorkItem>> {
+        let ids = result.ids();
+        match self.work_items_batch(&ids, options).await {
+            Err(Error(ErrorKind::Fault { code, .. }, _)) if code == StatusCode::NOT_FOUND => {
+                debug!("Work items batch API unavailable, fetching work items one by one");
+                let query = options
+                    .serialize()
+                    .map(|query| format!("?{}", query))
+                    .unwrap_or_default();
+                let mut work_items = Vec::new();
+                for id in ids {
+                    let uri = format!("/{}/_apis/wit/workItems/{}{}", self.org, id, query);
+                    // omitted, as the batch API does, when missing or unreadable
+                    match self.get::<WorkItem>(&uri).await {
+                        Ok(work_item) => work_items.push(work_item),
+                        Err(Error(ErrorKind::Fault { code, .. }, _))
+                            if code == StatusCode::NOT_FOUND =>
+                        {
+                            debug!("Work item {} not found, omitting it", id)
+                        }
+                        Err(err) => return Err(err),
+                    }
+                }
+                Ok(work_items)
+            }
+            result => result,
         }
-        Ok(work_items)
+    }
+
+    /// Run a WIQL query
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/{team}/_apis/wit/wiql?api-version=5.1
+    pub async fn query(&self, wiql: &Wiql, options: &WiqlOptions) -> Result<WorkItemQueryResult> {
+        let options = options.for_query(wiql);
+        let uri = self.wiql_uri(options.project.as_deref(), &options, "");
+        self.post(&uri, serde_json::to_vec(wiql)?).await
+    }
+
+    /// Run a saved query by id
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/wit/wiql/{id}?api-version=5.1
+    pub async fn saved_query<P, I>(
+        &self,
+        project: P,
+        id: I,
+        options: &WiqlOptions,
+    ) -> Result<WorkItemQueryResult>
+    where
+        P: AsRef<str>,
+        I: AsRef<str>,
+    {
+        let uri = self.wiql_uri(
+            Some(project.as_ref()),
+            options,
+            &format!("/{}", id.as_ref()),
+        );
+        self.get(&uri).await
+    }
+
+    /// Run a saved query by path, e.g. `Shared Queries/Triage`
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/queries/{path}?api-version=5.1
+    pub async fn saved_query_by_path<P, Q>(
+        &self,
+        project: P,
+        path: Q,
+        options: &WiqlOptions,
+    ) -> Result<WorkItemQueryResult>
+    where
+        P: AsRef<str>,
+        Q: AsRef<str>,
+    {
+        let query: QueryHierarchyItem = self
+            .get(&format!(
+                "/{}/{}/_apis/wit/queries/{}",
+                self.org,
+                project.as_ref(),
+                path.as_ref().trim_start_matches('/')
+            ))
+            .await?;
+        self.saved_query(project, query.id, options).await
+    }
+
+    fn wiql_uri(&self, project: Option<&str>, options: &WiqlOptions, more: &str) -> String {
+        let mut path = format!("/{}", self.org);
+        if let Some(project) = project {
+            path.push_str(&format!("/{}", project));
+            if let Some(team) = &options.team {
+                path.push_str(&format!("/{}", team));
+            }
+        }
+        path.push_str(&format!("/_apis/wit/wiql{}", more));
+        match options.serialize() {
+            Some(query) => format!("{}?{}", path, query),
+            None => path,
+        }
+    }
+
+    /// Fetch work items by id, in chunks of 200, preserving the order of `ids`
+    ///
+    /// Work items that don't exist or can't be read are omitted.
+    ///
+    /// POST https://dev.azure.com/{organization}/_apis/wit/workitemsbatch?api-version=5.1
+    pub async fn work_items_batch(
+        &self,
+        ids: &[usize],
+        options: &WorkItemBatchOptions,
+    ) -> Result<Vec<WorkItem>> {
+        let uri = format!("/{}/_apis/wit/workitemsbatch", self.org);
+        let batches = ids
+            .chunks(WORK_ITEMS_BATCH_SIZE)
+            .map(|ids| {
+                let body = serde_json::to_vec(&options.request(ids))?;
+                Ok(self.post::<Values<Option<WorkItem>>>(&uri, body))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let batches: Vec<_> = stream::iter(batches)
+            .buffered(options.concurrency())
+            .try_collect()
+            .await?;
+        let mut work_items: HashMap<usize, WorkItem> = batches
+            .into_iter()
+            .flat_map(|batch| batch.value.into_iter().flatten())
+            .map(|work_item| (work_item.id, work_item))
+            .collect();
+        Ok(ids.iter().filter_map(|id| work_items.remove(id)).collect())
     }
 
     pub async fn work_items(&self) -> Result<Vec<WorkItem>> {
@@ -283,6 +936,11 @@ impl AzureClient {
         self.query_work_items(&query).await
     }
 
+    /// Processes of the organization, see `Project::work_item_types` for the types a project uses
+    pub fn processes(&self) -> Processes {
+        Processes::new(self.clone())
+    }
+
     pub fn projects(&self) -> Projects {
         Projects::new(self.clone())
     }
@@ -302,11 +960,29 @@ impl AzureClient {
         Repository::new(self.clone(), project, repo)
     }
 
-    /// Get all repos in a organization
+    /// Stream the repositories of every project of the organization
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/git/repositories?api-version=5.1
+    pub fn org_repos(&self, options: &RepoListOptions) -> Stream<ReposValue> {
+        let mut uri = format!("/{}/_apis/git/repositories", self.org);
+        if let Some(query) = options.serialize() {
+            uri.push('?');
+            uri.push_str(&query);
+        }
+        self.get_stream(&uri, Pagination::ContinuationToken(None))
+    }
+
+    /// Stream the pull requests of every project of the organization matching `options`
     ///
-    /// GET https://dev.azure.com/{organization}/_apis/git/repositories?api-version=
-    pub fn org_repos(&self) {
-        // TODO
+    /// Pull requests can only be searched project by project, so projects are listed first.
+    pub fn org_pull_requests(&self, options: PullListOptions) -> Stream<GitPullRequest> {
+        let ops = self.clone();
+        Box::pin(
+            self.projects()
+                .stream(&ProjectOptions::default())
+                .map_ok(move |project| ops.project(project.name).pull_requests(options.clone()))
+                .try_flatten(),
+        )
     }
 
     /// Get all repos in a project
@@ -331,7 +1007,13 @@ impl AzureClient {
         authentication: AuthenticationConstraint,
     ) -> Future<(Url, Option<String>)> {
         let mut m = uri.to_owned();
-        m.push_str(&format!("?{}", self.api_version.to_string()));
+        // preview resources carry their own version
+        if !m.contains("api-version=") {
+            if !m.ends_with('?') {
+                m.push(if m.contains('?') { '&' } else { '?' });
+            }
+            m.push_str(&self.api_version.to_string());
+        }
         let parsed_url = m.parse::<Url>();
 
         match self.credentials(authentication) {
@@ -372,7 +1054,59 @@ impl AzureClient {
         body: Option<Vec<u8>>,
         media_type: MediaType,
         authentication: AuthenticationConstraint,
-    ) -> Future<(Option<Link>, Out)>
+    ) -> Future<(Option<Link>, Option<String>, Out)>
+    where
+        Out: DeserializeOwned + 'static + Send,
+    {
+        let instance = self.clone();
+        let uri = uri.to_owned();
+        Box::pin(async move {
+            let mut attempt = 1;
+            loop {
+                if let Some(delay) = instance.rate_limiter.delay() {
+                    debug!(
+                        "Rate limit nearly exhausted. Delaying request by {:?}",
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                let failure = match instance
+                    .request_once(
+                        method.clone(),
+                        &uri,
+                        body.clone(),
+                        media_type,
+                        authentication,
+                    )
+                    .await
+                {
+                    Ok(response) => return Ok(response),
+                    Err(failure) => failure,
+                };
+                match instance.retry_policy.delay(&method, attempt, &failure) {
+                    Some(delay) => {
+                        debug!(
+                            "Attempt {} failed: {}. Retrying in {:?}",
+                            attempt, failure.error, delay
+                        );
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => return Err(failure.error),
+                }
+            }
+        })
+    }
+
+    /// a single attempt at a request, telling apart failures that may be retried
+    fn request_once<Out>(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Option<Vec<u8>>,
+        media_type: MediaType,
+        authentication: AuthenticationConstraint,
+    ) -> Attempt<(Option<Link>, Option<String>, Out)>
     where
         Out: DeserializeOwned + 'static + Send,
     {
@@ -384,7 +1118,7 @@ impl AzureClient {
         let body2 = body.clone();
         let method2 = method.clone();
         let response = url_and_auth
-            .map_err(Error::from)
+            .map_err(Failure::from)
             .and_then(move |(url, auth)| {
                 #[cfg(not(feature = "httpcache"))]
                 let mut req = instance.client.request(method2, url);
@@ -401,10 +1135,12 @@ impl AzureClient {
                 };
 
                 req = req.header(USER_AGENT, &*instance.agent);
-                req = req.header(
-                    ACCEPT,
-                    &*format!("{}", qitem::<Mime>(From::from(media_type))),
-                );
+                // patches are sent as json-patch but answered with plain json
+                let accept = match media_type {
+                    MediaType::JsonPatch => MediaType::Json,
+                    media_type => media_type,
+                };
+                req = req.header(ACCEPT, &*format!("{}", qitem::<Mime>(From::from(accept))));
                 req = req.header(
                     "Content-Type",
                     &*format!("{}", qitem::<Mime>(From::from(media_type))),
@@ -419,10 +1155,9 @@ impl AzureClient {
                     req = req.body(Body::from(body));
                 }
                 debug!("Request: {:?}", &req);
-                req.send().map_err(Error::from)
+                req.send().map_err(Failure::from)
             });
 
-        #[cfg(feature = "httpcache")]
         let instance2 = self.clone();
 
         #[cfg(feature = "httpcache")]
@@ -434,16 +1169,25 @@ impl AzureClient {
             let (remaining, reset, etag) = get_header_values(response.headers());
 
             let status = response.status();
+            let retry_after = retry::retry_after(response.headers());
+            instance2
+                .rate_limiter
+                .update(RateLimitStatus::from_headers(response.headers()));
             let link = response
                 .headers()
                 .get(LINK)
                 .and_then(|l| l.to_str().ok())
                 .and_then(|l| l.parse().ok());
+            let continuation_token = response
+                .headers()
+                .get(X_MS_CONTINUATIONTOKEN)
+                .and_then(|t| t.to_str().ok())
+                .map(String::from);
 
             Box::pin(
                 response
                     .bytes()
-                    .map_err(Error::from)
+                    .map_err(Failure::from)
                     .and_then(move |response_body| async move {
                         if status.is_success() {
                             debug!(
@@ -467,8 +1211,8 @@ impl AzureClient {
                             }
                             let parsed_response : std::result::Result<Out, serde_json::error::Error> = if status == StatusCode::NO_CONTENT { serde_json::from_str("null") } else { serde_json::from_slice::<Out>(&response_body) };
                             parsed_response
-                                .map(|out| (link, out))
-                                .map_err(|error| ErrorKind::Codec(error).into())
+                                .map(|out| (link, continuation_token, out))
+                                .map_err(|error| Error::from(ErrorKind::Codec(error)).into())
                         } else if status == StatusCode::NOT_MODIFIED {
                             // only supported case is when client provides if-none-match
                             // header when cargo builds with --cfg feature="httpcache"
@@ -492,37 +1236,152 @@ impl AzureClient {
                                                             Link::new(vec![next])
                                                         }))
                                                 };
-                                                link.map(|link| (link, out))
+                                                link.map(|link| (link, continuation_token, out))
                                             })
                                     })
+                                    .map_err(Failure::from)
                             }
                             #[cfg(not(feature = "httpcache"))]
                             {
                                 unreachable!("this should not be reachable without the httpcache feature enabled")
                             }
                         } else {
-                            let error = match (remaining, reset) {
-                                (Some(remaining), Some(reset)) if remaining == 0 => {
-                                    let now = SystemTime::now()
-                                        .duration_since(UNIX_EPOCH)
-                                        .unwrap()
-                                        .as_secs();
-                                    ErrorKind::RateLimit {
-                                        reset: Duration::from_secs(u64::from(reset) - now),
-                                    }
+                            let (error, transient) = match (remaining, reset) {
+                                (Some(0), Some(reset)) => {
+                                    let reset = retry::until(u64::from(reset));
+                                    (ErrorKind::RateLimit { reset }.into(), true)
                                 }
-                                _ => ErrorKind::Fault {
-                                    code: status,
-                                    error: serde_json::from_slice(&response_body)?,
+                                _ => match serde_json::from_slice(&response_body) {
+                                    Ok(error) => (
+                                        ErrorKind::Fault {
+                                            code: status,
+                                            error,
+                                        }
+                                        .into(),
+                                        retry::is_transient(status),
+                                    ),
+                                    Err(error) => (Error::from(error), retry::is_transient(status)),
                                 },
                             };
-                            Err(error.into())
+                            Err(Failure {
+                                error,
+                                transient,
+                                retry_after,
+                            })
                         }
                     }),
             )
         }))
     }
 
+    /// a single request whose bodies are passed through as is, for uploads
+    ///
+    /// Not retried, as the body may be a stream that cannot be sent again.
+    async fn request_raw(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Option<Body>,
+        content_type: &str,
+    ) -> Result<reqwest::Response> {
+        self.request_raw_once(method, uri, body, content_type)
+            .await
+            .map_err(|failure| failure.error)
+    }
+
+    /// a single attempt at a request whose bodies are passed through as is
+    async fn request_raw_once(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Option<Body>,
+        content_type: &str,
+    ) -> std::result::Result<reqwest::Response, Failure> {
+        if let Some(delay) = self.rate_limiter.delay() {
+            debug!(
+                "Rate limit nearly exhausted. Delaying request by {:?}",
+                delay
+            );
+            tokio::time::sleep(delay).await;
+        }
+        let (url, auth) = self
+            .url_and_auth(
+                &(self.host.clone() + uri),
+                AuthenticationConstraint::Unconstrained,
+            )
+            .await?;
+        let mut req = self
+            .client
+            .request(method, url)
+            .header(USER_AGENT, &*self.agent)
+            .header(CONTENT_TYPE, content_type);
+        if let Some(auth_str) = auth {
+            req = req.header(AUTHORIZATION, &*auth_str);
+        }
+        if let Some(body) = body {
+            req = req.body(body);
+        }
+        debug!("Request: {:?}", &req);
+        let response = req.send().await?;
+        self.rate_limiter
+            .update(RateLimitStatus::from_headers(response.headers()));
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let retry_after = retry::retry_after(response.headers());
+        let body = response.bytes().await?;
+        let error = serde_json::from_slice(&body).unwrap_or_else(|_| ClientError {
+            message: String::from_utf8_lossy(&body).into_owned(),
+            errors: None,
+            documentation_url: None,
+        });
+        Err(Failure {
+            error: ErrorKind::Fault {
+                code: status,
+                error,
+            }
+            .into(),
+            transient: retry::is_transient(status),
+            retry_after,
+        })
+    }
+
+    /// a get request whose response body is streamed back as is
+    ///
+    /// Failed attempts are retried according to the retry policy, until the body starts streaming.
+    fn download(&self, uri: &str) -> Stream<Bytes> {
+        let instance = self.clone();
+        let uri = uri.to_owned();
+        Box::pin(
+            async move {
+                let mut attempt = 1;
+                let response = loop {
+                    let failure = match instance
+                        .request_raw_once(Method::GET, &uri, None, "application/octet-stream")
+                        .await
+                    {
+                        Ok(response) => break response,
+                        Err(failure) => failure,
+                    };
+                    match instance.retry_policy.delay(&Method::GET, attempt, &failure) {
+                        Some(delay) => {
+                            debug!(
+                                "Attempt {} failed: {}. Retrying in {:?}",
+                                attempt, failure.error, delay
+                            );
+                            tokio::time::sleep(delay).await;
+                            attempt += 1;
+                        }
+                        None => return Err(failure.error),
+                    }
+                };
+                Ok::<_, Error>(response.bytes_stream().map_err(Error::from))
+            }
+            .try_flatten_stream(),
+        )
+    }
+
     fn request_entity<D>(
         &self,
         method: Method,
@@ -536,7 +1395,83 @@ impl AzureClient {
     {
         Box::pin(
             self.request(method, uri, body, media_type, authentication)
-                .map_ok(|(_, entity)| entity),
+                .map_ok(|(_, _, entity)| entity),
+        )
+    }
+
+    /// a get request that also returns the continuation token of the next page, if any
+    fn get_page<D>(&self, uri: &str) -> Future<(Option<String>, D)>
+    where
+        D: DeserializeOwned + 'static + Send,
+    {
+        Box::pin(
+            self.request(
+                Method::GET,
+                &(self.host.clone() + uri),
+                None,
+                MediaType::Json,
+                AuthenticationConstraint::Unconstrained,
+            )
+            .map_ok(|(_, continuation_token, entity)| (continuation_token, entity)),
+        )
+    }
+
+    /// Stream every item of a list endpoint, requesting pages until exhaustion
+    fn get_stream<T>(&self, uri: &str, pagination: Pagination) -> Stream<T>
+    where
+        T: DeserializeOwned + 'static + Send,
+    {
+        let instance = self.clone();
+        let uri = uri.to_owned();
+        let pages = stream::try_unfold(Some(pagination), move |pagination| {
+            let instance = instance.clone();
+            let uri = uri.clone();
+            async move {
+                let pagination: Pagination = match pagination {
+                    Some(pagination) => pagination,
+                    None => return Ok(None),
+                };
+                let (continuation_token, page) = instance
+                    .get_page::<Values<T>>(&pagination.uri(&uri))
+                    .await?;
+                let next = pagination.next(continuation_token, page.value.len());
+                Ok::<_, Error>(Some((page.value, next)))
+            }
+        });
+        Box::pin(
+            pages
+                .map_ok(|values| stream::iter(values.into_iter().map(Ok)))
+                .try_flatten(),
+        )
+    }
+
+    /// Stream every item of a list endpoint queried by posting `message`, requesting pages until exhaustion
+    fn post_stream<T>(&self, uri: &str, message: Vec<u8>, pagination: Pagination) -> Stream<T>
+    where
+        T: DeserializeOwned + 'static + Send,
+    {
+        let instance = self.clone();
+        let uri = uri.to_owned();
+        let pages = stream::try_unfold(Some(pagination), move |pagination| {
+            let instance = instance.clone();
+            let uri = uri.clone();
+            let message = message.clone();
+            async move {
+                let pagination: Pagination = match pagination {
+                    Some(pagination) => pagination,
+                    None => return Ok(None),
+                };
+                let page = instance
+                    .post::<Values<T>>(&pagination.uri(&uri), message)
+                    .await?;
+                let next = pagination.next(None, page.value.len());
+                Ok::<_, Error>(Some((page.value, next)))
+            }
+        });
+        Box::pin(
+            pages
+                .map_ok(|values| stream::iter(values.into_iter().map(Ok)))
+                .try_flatten(),
         )
     }
 
@@ -547,6 +1482,14 @@ impl AzureClient {
         self.get_media(uri, MediaType::Json)
     }
 
+    /// a get request on a list endpoint returning a single page
+    fn get_values<T>(&self, uri: &str) -> Future<Vec<T>>
+    where
+        T: DeserializeOwned + 'static + Send,
+    {
+        Box::pin(self.get::<Values<T>>(uri).map_ok(|values| values.value))
+    }
+
     fn get_media<D>(&self, uri: &str, media: MediaType) -> Future<D>
     where
         D: DeserializeOwned + 'static + Send,
@@ -606,6 +1549,19 @@ impl AzureClient {
         )
     }
 
+    fn put<D>(&self, uri: &str, message: Vec<u8>) -> Future<D>
+    where
+        D: DeserializeOwned + 'static + Send,
+    {
+        self.request_entity(
+            Method::PUT,
+            &(self.host.clone() + uri),
+            Some(message),
+            MediaType::Json,
+            AuthenticationConstraint::Unconstrained,
+        )
+    }
+
     fn patch_media<D>(&self, uri: &str, message: Vec<u8>, media: MediaType) -> Future<D>
     where
         D: DeserializeOwned + 'static + Send,
@@ -627,6 +1583,97 @@ impl AzureClient {
     }
 }
 
+fn expand_relations() -> WorkItemUpdateOptions {
+    WorkItemUpdateOptions::builder()
+        .expand(WorkItemExpand::Relations)
+        .build()
+}
+
+/// The envelope Azure wraps list results in
+#[derive(Deserialize)]
+struct Values<T> {
+    value: Vec<T>,
+}
+
+/// How a list endpoint splits its results into pages
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Pagination {
+    /// Follow the `x-ms-continuationtoken` header through the `continuationToken` parameter
+    ContinuationToken(Option<String>),
+    /// Request `top` items at a time, advancing `$skip` by the number of items received
+    TopSkip { top: u32, skip: u32 },
+    /// Like `TopSkip`, through the `searchCriteria.$top` and `searchCriteria.$skip` parameters
+    SearchCriteria { top: u32, skip: u32 },
+}
+
+impl Pagination {
+    pub(crate) fn top_skip(top: Option<u32>, skip: Option<u32>) -> Self {
+        Pagination::TopSkip {
+            top: top.unwrap_or(DEFAULT_PAGE_SIZE),
+            skip: skip.unwrap_or(0),
+        }
+    }
+
+    pub(crate) fn search_criteria(top: Option<u32>, skip: Option<u32>) -> Self {
+        Pagination::SearchCriteria {
+            top: top.unwrap_or(DEFAULT_PAGE_SIZE),
+            skip: skip.unwrap_or(0),
+        }
+    }
+
+    /// uri of the page described by `self`
+    fn uri(&self, uri: &str) -> String {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        match self {
+            Pagination::ContinuationToken(None) => return uri.to_owned(),
+            Pagination::ContinuationToken(Some(token)) => {
+                query.append_pair("continuationToken", token);
+            }
+            Pagination::TopSkip { top, skip } => {
+                query
+                    .append_pair("$top", &top.to_string())
+                    .append_pair("$skip", &skip.to_string());
+            }
+            Pagination::SearchCriteria { top, skip } => {
+                query
+                    .append_pair("searchCriteria.$top", &top.to_string())
+                    .append_pair("searchCriteria.$skip", &skip.to_string());
+            }
+        }
+        let separator = if uri.contains('?') { '&' } else { '?' };
+        format!("{}{}{}", uri, separator, query.finish())
+    }
+
+    /// pagination of the page following `self`, or `None` once the results are exhausted
+    fn next(self, continuation_token: Option<String>, received: usize) -> Option<Self> {
+        match self {
+            Pagination::ContinuationToken(_) => {
+                continuation_token.map(|token| Pagination::ContinuationToken(Some(token)))
+            }
+            Pagination::TopSkip { top, skip } => {
+                if received == 0 || received < top as usize {
+                    None
+                } else {
+                    Some(Pagination::TopSkip {
+                        top,
+                        skip: skip + received as u32,
+                    })
+                }
+            }
+            Pagination::SearchCriteria { top, skip } => {
+                if received == 0 || received < top as usize {
+                    None
+                } else {
+                    Some(Pagination::SearchCriteria {
+                        top,
+                        skip: skip + received as u32,
+                    })
+                }
+            }
+        }
+    }
+}
+
 #[allow(dead_code)]
 fn next_link(l: &Link) -> Option<String> {
     l.values()
@@ -683,6 +1730,46 @@ mod tests {
         assert_eq!(default, SortDirection::Asc)
     }
 
+    #[test]
+    fn continuation_token_pagination() {
+        let first = Pagination::ContinuationToken(None);
+        assert_eq!(first.uri("/org/_apis/projects"), "/org/_apis/projects");
+
+        let next = first.next(Some("a b+c".into()), 100).unwrap();
+        assert_eq!(
+            next.uri("/org/_apis/projects?stateFilter=all"),
+            "/org/_apis/projects?stateFilter=all&continuationToken=a+b%2Bc"
+        );
+        assert_eq!(next.next(None, 100), None);
+    }
+
+    #[test]
+    fn top_skip_pagination() {
+        let first = Pagination::top_skip(Some(2), None);
+        assert_eq!(
+            first.uri("/pullrequests"),
+            "/pullrequests?%24top=2&%24skip=0"
+        );
+
+        let second = first.next(None, 2).unwrap();
+        assert_eq!(second, Pagination::TopSkip { top: 2, skip: 2 });
+        assert_eq!(second.clone().next(None, 1), None);
+        assert_eq!(second.next(None, 0), None);
+    }
+
+    #[test]
+    fn search_criteria_pagination() {
+        let first = Pagination::search_criteria(Some(2), Some(4));
+        assert_eq!(
+            first.uri("/commits?searchCriteria.itemPath=%2Fsrc"),
+            "/commits?searchCriteria.itemPath=%2Fsrc&searchCriteria.%24top=2&searchCriteria.%24skip=4"
+        );
+
+        let second = first.next(None, 2).unwrap();
+        assert_eq!(second, Pagination::SearchCriteria { top: 2, skip: 6 });
+        assert_eq!(second.next(None, 1), None);
+    }
+
     #[test]
     #[cfg(not(feature = "httpcache"))]
     fn header_values() {
@@ -0,0 +1,259 @@
+//! Policy evaluations
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/policy/evaluations?view=azure-devops-rest-5.1
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use url::form_urlencoded;
+
+use crate::identity::IdentityRef;
+use crate::{AzureClient, Future};
+
+/// Policies of a project
+pub struct Policies {
+    ops: AzureClient,
+    project: String,
+}
+
+impl Policies {
+    #[doc(hidden)]
+    pub fn new<P>(ops: AzureClient, project: P) -> Self
+    where
+        P: Into<String>,
+    {
+        Self {
+            ops,
+            project: project.into(),
+        }
+    }
+
+    /// Evaluations of the policies applying to an artifact, e.g. `GitPullRequest::artifact_id`
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/policy/evaluations?artifactId={artifactId}&includeNotApplicable={includeNotApplicable}&api-version=5.1-preview.1
+    pub fn evaluations(
+        &self,
+        artifact_id: &str,
+        include_not_applicable: bool,
+    ) -> Future<Vec<PolicyEvaluationRecord>> {
+        let query: String = form_urlencoded::Serializer::new(String::new())
+            .append_pair("artifactId", artifact_id)
+            .append_pair("includeNotApplicable", &include_not_applicable.to_string())
+            .finish();
+        self.ops.get_values(&self.path(&format!("?{}&", query)))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/policy/evaluations/{evaluationId}?api-version=5.1-preview.1
+    pub fn evaluation(&self, evaluation_id: &str) -> Future<PolicyEvaluationRecord> {
+        self.ops.get(&self.path(&format!("/{}?", evaluation_id)))
+    }
+
+    /// Evaluate a policy again, e.g. to rebuild a pull request
+    ///
+    /// PATCH https://dev.azure.com/{organization}/{project}/_apis/policy/evaluations/{evaluationId}?api-version=5.1-preview.1
+    pub fn requeue(&self, evaluation_id: &str) -> Future<PolicyEvaluationRecord> {
+        self.ops
+            .patch(&self.path(&format!("/{}?", evaluation_id)), Vec::new())
+    }
+
+    fn path(&self, more: &str) -> String {
+        format!(
+            "/{}/{}/_apis/policy/evaluations{}{}",
+            self.ops.org,
+            self.project,
+            more,
+            self.ops.api_version.preview(1)
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyEvaluationRecord {
+    pub evaluation_id: String,
+    pub artifact_id: Option<String>,
+    pub status: Option<PolicyEvaluationStatus>,
+    pub configuration: Option<PolicyConfiguration>,
+    pub started_date: Option<DateTime<Utc>>,
+    pub completed_date: Option<DateTime<Utc>>,
+    /// policy specific details, e.g. the build of a build policy
+    pub context: Option<Value>,
+}
+
+impl PolicyEvaluationRecord {
+    /// whether the evaluation keeps the artifact from completing
+    pub fn is_blocking(&self) -> bool {
+        let blocking = self
+            .configuration
+            .as_ref()
+            .is_some_and(|configuration| configuration.is_blocking);
+        blocking
+            && !matches!(
+                self.status,
+                Some(PolicyEvaluationStatus::Approved)
+                    | Some(PolicyEvaluationStatus::NotApplicable)
+            )
+    }
+}
+
+/// enum representation of Azure Policy Evaluation Status
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PolicyEvaluationStatus {
+    Queued,
+    Running,
+    Approved,
+    Rejected,
+    NotApplicable,
+    Broken,
+}
+
+impl fmt::Display for PolicyEvaluationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PolicyEvaluationStatus::Queued => "queued",
+            PolicyEvaluationStatus::Running => "running",
+            PolicyEvaluationStatus::Approved => "approved",
+            PolicyEvaluationStatus::Rejected => "rejected",
+            PolicyEvaluationStatus::NotApplicable => "notApplicable",
+            PolicyEvaluationStatus::Broken => "broken",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyConfiguration {
+    pub id: u64,
+    pub revision: Option<u32>,
+    #[serde(rename = "type")]
+    pub policy_type: Option<PolicyTypeRef>,
+    #[serde(default)]
+    pub is_blocking: bool,
+    #[serde(default)]
+    pub is_enabled: bool,
+    #[serde(default)]
+    pub is_deleted: bool,
+    pub settings: Option<Value>,
+    pub created_by: Option<IdentityRef>,
+    pub created_date: Option<DateTime<Utc>>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyTypeRef {
+    pub id: String,
+    pub display_name: Option<String>,
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn requeue_rejected_evaluations_of_pull_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/org/project/_apis/git/repositories/repo/pullrequests/3",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "pullRequestId": 3,
+                "artifactId": "vstfs:///CodeReview/CodeReviewId/p1/3"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/project/_apis/policy/evaluations"))
+            .and(query_param(
+                "artifactId",
+                "vstfs:///CodeReview/CodeReviewId/p1/3",
+            ))
+            .and(query_param("api-version", "5.1-preview.1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 2,
+                "value": [{
+                    "evaluationId": "e1",
+                    "status": "rejected",
+                    "configuration": {
+                        "id": 4,
+                        "isBlocking": true,
+                        "isEnabled": true,
+                        "type": { "id": "t1", "displayName": "Build" }
+                    }
+                }, {
+                    "evaluationId": "e2",
+                    "status": "approved",
+                    "configuration": { "id": 5, "isBlocking": true }
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/org/project/_apis/policy/evaluations/e1"))
+            .and(query_param("api-version", "5.1-preview.1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "evaluationId": "e1",
+                "status": "queued"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let evaluations = client
+            .repo("project", "repo")
+            .pull(3)
+            .policy_evaluations()
+            .await
+            .unwrap();
+        let blocking: Vec<&PolicyEvaluationRecord> = evaluations
+            .iter()
+            .filter(|evaluation| evaluation.is_blocking())
+            .collect();
+        assert_eq!(blocking.len(), 1);
+
+        let requeued = client
+            .project("project")
+            .policies()
+            .requeue(&blocking[0].evaluation_id)
+            .await
+            .unwrap();
+        assert_eq!(requeued.status, Some(PolicyEvaluationStatus::Queued));
+    }
+
+    #[tokio::test]
+    async fn fail_evaluations_of_pull_request_without_artifact_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/org/project/_apis/git/repositories/repo/pullrequests/3",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "pullRequestId": 3 })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/project/_apis/policy/evaluations"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let result = client
+            .repo("project", "repo")
+            .pull(3)
+            .policy_evaluations()
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! Processes
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/processes/processes?view=azure-devops-rest-5.1
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::work_item_types::{FieldType, WorkItemIcon};
+use crate::{AzureClient, Future};
+
+/// Processes of an organization and the work item types they define
+pub struct Processes {
+    ops: AzureClient,
+}
+
+impl Processes {
+    #[doc(hidden)]
+    pub fn new(ops: AzureClient) -> Self {
+        Self { ops }
+    }
+
+    /// List processes, along with the projects using them
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/work/processes?$expand=projects&api-version=5.1-preview.2
+    pub fn list(&self) -> Future<Vec<Process>> {
+        self.ops.get_values(&self.path("", "?$expand=projects&", 2))
+    }
+
+    /// GET https://dev.azure.com/{organization}/_apis/work/processes/{processTypeId}?$expand=projects&api-version=5.1-preview.2
+    pub fn get(&self, process_id: &str) -> Future<Process> {
+        self.ops
+            .get(&self.path(&format!("/{}", process_id), "?$expand=projects&", 2))
+    }
+
+    /// Work item types of a process, along with their states
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/work/processes/{processId}/workitemtypes?$expand=states&api-version=5.1-preview.2
+    pub fn work_item_types(&self, process_id: &str) -> Future<Vec<ProcessWorkItemType>> {
+        self.ops.get_values(&self.path(
+            &format!("/{}/workitemtypes", process_id),
+            "?$expand=states&",
+            2,
+        ))
+    }
+
+    /// GET https://dev.azure.com/{organization}/_apis/work/processes/{processId}/workItemTypes/{witRefName}/states?api-version=5.1-preview.1
+    pub fn states(
+        &self,
+        process_id: &str,
+        work_item_type: &str,
+    ) -> Future<Vec<ProcessWorkItemTypeState>> {
+        self.ops.get_values(&self.path(
+            &format!("/{}/workItemTypes/{}/states", process_id, work_item_type),
+            "?",
+            1,
+        ))
+    }
+
+    /// Fields of a work item type, along with their allowed values
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/work/processes/{processId}/workItemTypes/{witRefName}/fields?$expand=allowedValues&api-version=5.1-preview.2
+    pub fn fields(
+        &self,
+        process_id: &str,
+        work_item_type: &str,
+    ) -> Future<Vec<ProcessWorkItemTypeField>> {
+        self.ops.get_values(&self.path(
+            &format!("/{}/workItemTypes/{}/fields", process_id, work_item_type),
+            "?$expand=allowedValues&",
+            2,
+        ))
+    }
+
+    /// Values of a picklist field, see `WorkItemField::picklist_id`
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/work/processes/lists/{listId}?api-version=5.1-preview.1
+    pub fn picklist(&self, list_id: &str) -> Future<PickList> {
+        self.ops
+            .get(&self.path(&format!("/lists/{}", list_id), "?", 1))
+    }
+
+    fn path(&self, more: &str, query: &str, preview: u8) -> String {
+        format!(
+            "/{}/_apis/work/processes{}{}{}",
+            self.ops.org,
+            more,
+            query,
+            self.ops.api_version.preview(preview)
+        )
+    }
+}
+
+/// https://docs.microsoft.com/en-us/rest/api/azure/devops/processes/processes/get?view=azure-devops-rest-5.1#processinfo
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Process {
+    pub type_id: String,
+    pub name: String,
+    pub reference_name: Option<String>,
+    pub description: Option<String>,
+    pub parent_process_type_id: Option<String>,
+    #[serde(default)]
+    pub is_default: bool,
+    #[serde(default)]
+    pub is_enabled: bool,
+    pub customization_type: Option<CustomizationType>,
+    #[serde(default)]
+    pub projects: Vec<ProcessProject>,
+}
+
+/// enum representation of how a process or one of its parts came to be
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CustomizationType {
+    System,
+    Inherited,
+    Custom,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ProcessProject {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessWorkItemType {
+    pub reference_name: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<WorkItemIcon>,
+    #[serde(default)]
+    pub is_disabled: bool,
+    /// reference name of the parent work item type, for inherited types
+    pub inherits: Option<String>,
+    pub customization: Option<CustomizationType>,
+    #[serde(default)]
+    pub states: Vec<ProcessWorkItemTypeState>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessWorkItemTypeState {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    /// `Proposed`, `InProgress`, `Resolved`, `Completed` or `Removed`
+    pub state_category: Option<String>,
+    pub order: Option<i32>,
+    pub customization_type: Option<CustomizationType>,
+    #[serde(default)]
+    pub hidden: bool,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessWorkItemTypeField {
+    pub reference_name: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: Option<FieldType>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub read_only: bool,
+    pub default_value: Option<Value>,
+    #[serde(default)]
+    pub allowed_values: Vec<Value>,
+    pub customization: Option<CustomizationType>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PickList {
+    pub id: String,
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub list_type: Option<String>,
+    /// whether values outside of `items` are accepted
+    #[serde(default)]
+    pub is_suggested: bool,
+    #[serde(default)]
+    pub items: Vec<Value>,
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn list_process_work_item_types() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/work/processes/p1/workitemtypes"))
+            .and(query_param("$expand", "states"))
+            .and(query_param("api-version", "5.1-preview.2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 1,
+                "value": [{
+                    "referenceName": "Custom.Bug",
+                    "name": "Bug",
+                    "inherits": "Microsoft.VSTS.WorkItemTypes.Bug",
+                    "customization": "inherited",
+                    "isDisabled": false,
+                    "states": [{
+                        "id": "s1",
+                        "name": "Triaged",
+                        "stateCategory": "InProgress",
+                        "order": 2,
+                        "customizationType": "custom"
+                    }]
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let types = client.processes().work_item_types("p1").await.unwrap();
+        assert_eq!(types[0].customization, Some(CustomizationType::Inherited));
+        assert_eq!(types[0].states[0].name, "Triaged");
+        assert_eq!(
+            types[0].states[0].customization_type,
+            Some(CustomizationType::Custom)
+        );
+    }
+}
@@ -3,10 +3,16 @@ use std::collections::HashMap;
 
 use url::form_urlencoded;
 
-use crate::{AzureClient, Future};
+use crate::boards::Boards;
+use crate::classification_nodes::{ClassificationNodes, TreeStructureGroup};
+use crate::policies::Policies;
+use crate::pull_requests::{pulls, GitPullRequest, PullListOptions};
+use crate::teams::{Team, Teams};
+use crate::work_item_types::WorkItemTypes;
+use crate::{AzureClient, Future, Pagination, Stream};
 pub use new_project_options::{ProjectsOptions, ProjectsOptionsBuilder};
 pub use new_project_response::ProjectStatus;
-pub use project_list_response::ProjectsResponse;
+pub use project_list_response::{ProjectsResponse, Value as ProjectsValue};
 pub use project_response::ProjectResponse;
 
 pub struct Projects {
@@ -25,18 +31,28 @@ impl Projects {
     }
 
     fn path(&self, more: &str) -> String {
-        format!("/{}/{}/_apis/projects", self.ops.org, more)
+        format!("/{}/_apis/projects{}", self.ops.org, more)
     }
 
     /// List existing projects
+    ///
+    /// Only the first page is returned, see `stream` to list every project
     pub fn list(&self, options: &ProjectOptions) -> Future<ProjectsResponse> {
+        self.ops.get(&self.uri(options))
+    }
+
+    /// Stream every project, following continuation tokens until exhaustion
+    pub fn stream(&self, options: &ProjectOptions) -> Stream<ProjectsValue> {
+        self.ops
+            .get_stream(&self.uri(options), Pagination::ContinuationToken(None))
+    }
+
+    fn uri(&self, options: &ProjectOptions) -> String {
         let mut uri = vec![self.path("")];
         if let Some(query) = options.serialize() {
             uri.push(query);
         }
-
-        // FIXME: It seems incorrect. Only one ? it used in a URL to start a query and they should be separated with &.
-        self.ops.get(&uri.join("?"))
+        uri.join("?")
     }
 }
 mod new_project_response {
@@ -189,6 +205,10 @@ pub struct ProjectOptions {
 }
 
 impl ProjectOptions {
+    pub fn builder() -> ProjectOptionsBuilder {
+        ProjectOptionsBuilder::default()
+    }
+
     /// serialize options as a string. returns None if no options are defined
     pub fn serialize(&self) -> Option<String> {
         if self.params.is_empty() {
@@ -202,6 +222,54 @@ impl ProjectOptions {
     }
 }
 
+#[derive(Default)]
+pub struct ProjectOptionsBuilder(ProjectOptions);
+
+impl ProjectOptionsBuilder {
+    /// filter on team projects in a specific state, e.g. `wellFormed` or `all`
+    pub fn state_filter<S>(&mut self, state_filter: S) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.0.params.insert("stateFilter", state_filter.into());
+        self
+    }
+
+    pub fn top(&mut self, top: u32) -> &mut Self {
+        self.0.params.insert("$top", top.to_string());
+        self
+    }
+
+    pub fn skip(&mut self, skip: u32) -> &mut Self {
+        self.0.params.insert("$skip", skip.to_string());
+        self
+    }
+
+    pub fn continuation_token<T>(&mut self, continuation_token: T) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.0
+            .params
+            .insert("continuationToken", continuation_token.into());
+        self
+    }
+
+    pub fn get_default_team_image_url(&mut self, get_default_team_image_url: bool) -> &mut Self {
+        self.0.params.insert(
+            "getDefaultTeamImageUrl",
+            get_default_team_image_url.to_string(),
+        );
+        self
+    }
+
+    pub fn build(&self) -> ProjectOptions {
+        ProjectOptions {
+            params: self.0.params.clone(),
+        }
+    }
+}
+
 pub struct Project {
     ops: AzureClient,
     project: String,
@@ -227,6 +295,62 @@ impl Project {
         self.ops.delete(&self.path(""))
     }
 
+    pub fn teams(&self) -> Teams {
+        Teams::new(self.ops.clone(), self.project.as_str())
+    }
+
+    /// A team of the project, by name or id
+    pub fn team<T>(&self, team: T) -> Team
+    where
+        T: Into<String>,
+    {
+        Team::new(self.ops.clone(), self.project.as_str(), team)
+    }
+
+    /// Backlogs, boards and taskboards of a team of the project
+    pub fn boards<T>(&self, team: T) -> Boards
+    where
+        T: Into<String>,
+    {
+        Boards::new(self.ops.clone(), self.project.as_str(), team)
+    }
+
+    /// The area path tree
+    pub fn areas(&self) -> ClassificationNodes {
+        ClassificationNodes::new(
+            self.ops.clone(),
+            self.project.as_str(),
+            TreeStructureGroup::Areas,
+        )
+    }
+
+    /// The iteration path tree
+    pub fn iterations(&self) -> ClassificationNodes {
+        ClassificationNodes::new(
+            self.ops.clone(),
+            self.project.as_str(),
+            TreeStructureGroup::Iterations,
+        )
+    }
+
+    /// Work item types, fields and states of the project
+    pub fn work_item_types(&self) -> WorkItemTypes {
+        WorkItemTypes::new(self.ops.clone(), self.project.as_str())
+    }
+
+    /// Policies of the project and their evaluations
+    pub fn policies(&self) -> Policies {
+        Policies::new(self.ops.clone(), self.project.as_str())
+    }
+
+    /// Stream the pull requests of every repository of the project matching `options`
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/pullrequests?api-version=5.1
+    pub fn pull_requests(&self, options: PullListOptions) -> Stream<GitPullRequest> {
+        let path = format!("/{}/{}/_apis/git/pullrequests", self.ops.org, self.project);
+        pulls::stream(&self.ops, &path, options)
+    }
+
     // GET https://dev.azure.com/{organization}/_apis/projects/{projectId}?api-version=5.1
     fn path(&self, more: &str) -> String {
         format!("/{}/_apis/projects/{}{}", self.ops.org, self.project, more)
@@ -282,3 +406,62 @@ mod project_response {
         pub url: String,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    fn project(id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "name": id,
+            "url": format!("https://dev.azure.com/org/_apis/projects/{}", id),
+            "state": "wellFormed",
+            "revision": 1,
+            "visibility": "private",
+            "lastUpdateTime": "2020-05-01T10:15:30Z"
+        })
+    }
+
+    #[tokio::test]
+    async fn stream_projects_across_pages() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/projects"))
+            .and(query_param("continuationToken", "next"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 1,
+                "value": [project("p2")]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/projects"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ms-continuationtoken", "next")
+                    .set_body_json(serde_json::json!({
+                        "count": 1,
+                        "value": [project("p1")]
+                    })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let projects: Vec<ProjectsValue> = client
+            .projects()
+            .stream(&ProjectOptions::default())
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<&str> = projects.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["p1", "p2"]);
+    }
+}
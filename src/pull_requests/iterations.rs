@@ -0,0 +1,193 @@
+//! Pull request iterations and their changes
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/pull%20request%20iterations?view=azure-devops-rest-5.1
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/pull%20request%20iteration%20changes?view=azure-devops-rest-5.1
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::GitCommitRef;
+use crate::identity::IdentityRef;
+
+/// A push to the source branch of a pull request
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPullRequestIteration {
+    pub id: u32,
+    pub description: Option<String>,
+    pub author: Option<IdentityRef>,
+    pub created_date: Option<DateTime<Utc>>,
+    pub updated_date: Option<DateTime<Utc>>,
+    /// `create`, `push`, `forcePush`, `rebase`, `retarget` or `resolveConflicts`
+    pub reason: Option<String>,
+    pub source_ref_commit: Option<GitCommitRef>,
+    pub target_ref_commit: Option<GitCommitRef>,
+    pub common_ref_commit: Option<GitCommitRef>,
+    #[serde(default)]
+    pub commits: Vec<GitCommitRef>,
+    #[serde(default)]
+    pub has_more_commits: bool,
+}
+
+/// A page of changes, pointing to the next one through `next_skip`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GitPullRequestIterationChanges {
+    #[serde(default)]
+    pub(crate) change_entries: Vec<GitPullRequestChange>,
+    #[serde(default)]
+    pub(crate) next_skip: u32,
+    #[serde(default)]
+    pub(crate) next_top: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPullRequestChange {
+    /// identifies a file across iterations, even when renamed
+    pub change_tracking_id: Option<u64>,
+    pub change_id: Option<u64>,
+    pub item: Option<GitItemRef>,
+    #[serde(rename = "changeType", deserialize_with = "change_types", default)]
+    pub change_types: Vec<ChangeType>,
+    /// path of a renamed file before the rename
+    pub original_path: Option<String>,
+}
+
+impl GitPullRequestChange {
+    /// path of the changed file or folder
+    pub fn path(&self) -> Option<&str> {
+        self.item.as_ref().and_then(|item| item.path.as_deref())
+    }
+
+    pub fn is_folder(&self) -> bool {
+        self.item.as_ref().is_some_and(GitItemRef::is_tree)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitItemRef {
+    pub object_id: Option<String>,
+    pub original_object_id: Option<String>,
+    pub path: Option<String>,
+    /// `blob` for files and `tree` for folders
+    pub git_object_type: Option<String>,
+    #[serde(default)]
+    pub is_folder: bool,
+    pub commit_id: Option<String>,
+    pub url: Option<String>,
+}
+
+impl GitItemRef {
+    /// whether the item is a folder, which some responses only tell through `git_object_type`
+    pub fn is_tree(&self) -> bool {
+        self.is_folder || self.git_object_type.as_deref() == Some("tree")
+    }
+}
+
+/// enum representation of the kinds of change of a file, a change combining one or more of them
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeType {
+    None,
+    Add,
+    Edit,
+    Encoding,
+    Rename,
+    Delete,
+    Undelete,
+    Branch,
+    Merge,
+    Lock,
+    Rollback,
+    SourceRename,
+    TargetRename,
+    Property,
+    #[serde(other)]
+    Other,
+}
+
+/// change types are sent as flags, e.g. `"edit, rename"`
+pub(crate) fn change_types<'de, D>(deserializer: D) -> Result<Vec<ChangeType>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let flags = String::deserialize(deserializer)?;
+    Ok(flags
+        .split(',')
+        .map(str::trim)
+        .filter(|flag| !flag.is_empty())
+        .map(|flag| {
+            serde_json::from_value(serde_json::Value::String(flag.to_owned()))
+                .unwrap_or(ChangeType::Other)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::AzureClient;
+
+    const ITERATIONS: &str = "/org/project/_apis/git/repositories/repo/pullrequests/3/iterations";
+
+    #[tokio::test]
+    async fn changed_files_since_last_iteration() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/3/changes", ITERATIONS)))
+            .and(query_param("$compareTo", "2"))
+            .and(query_param("$skip", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "changeEntries": [{
+                    "changeTrackingId": 1,
+                    "changeId": 1,
+                    "item": { "objectId": "a1", "path": "/src/lib.rs" },
+                    "changeType": "edit"
+                }, {
+                    "changeTrackingId": 2,
+                    "changeId": 2,
+                    "item": { "path": "/src", "gitObjectType": "tree" },
+                    "changeType": "edit"
+                }],
+                "nextSkip": 2,
+                "nextTop": 2
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/3/changes", ITERATIONS)))
+            .and(query_param("$compareTo", "2"))
+            .and(query_param("$skip", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "changeEntries": [{
+                    "changeTrackingId": 3,
+                    "changeId": 3,
+                    "item": { "path": "/src/client.rs" },
+                    "originalPath": "/src/http.rs",
+                    "changeType": "edit, rename"
+                }],
+                "nextSkip": 0,
+                "nextTop": 0
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let changed = client
+            .repo("project", "repo")
+            .pull(3)
+            .changed_files(Some(2), 3)
+            .await
+            .unwrap();
+        let paths: Vec<&str> = changed.keys().map(String::as_str).collect();
+        assert_eq!(paths, vec!["/src/client.rs", "/src/lib.rs"]);
+        assert_eq!(
+            changed["/src/client.rs"],
+            vec![ChangeType::Edit, ChangeType::Rename]
+        );
+    }
+}
@@ -1,326 +1,176 @@
 use std::fmt;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+pub use git_pull_request::{
+    GitCommitRef, GitPullRequest, GitRepositoryRef, GitUserDate, ResourceRef, TeamProjectReference,
+    WebApiTagDefinition,
+};
 pub use pr_create_options::PROption;
-pub use pr_create_response::PullRequestCreateResponse;
-pub use pr_get_response::PullRequestResponse;
 pub use pull_request_list_options::{PullListOptions, PullListOptionsBuilder};
-pub use pull_requests_reponse::PullRequestsResponse;
-pub use pull_update_options::{PullUpdateOptions, PullUpdateOptionsBuilder};
+pub use pull_update_options::{
+    CompletionOptions, CompletionOptionsBuilder, PullUpdateOptions, PullUpdateOptionsBuilder,
+};
 
+pub mod iterations;
 pub mod pull;
 pub mod pulls;
+pub mod reviewers;
+pub mod statuses;
+pub mod threads;
 
+pub use iterations::{ChangeType, GitItemRef, GitPullRequestChange, GitPullRequestIteration};
 pub use pull::PullRequest;
 pub use pulls::PullRequests;
+pub use reviewers::{IdentityRefWithVote, Vote};
+pub use statuses::{
+    GitPullRequestStatus, GitPullRequestStatusOptions, GitStatusContext, GitStatusState,
+};
+pub use threads::{
+    CommentPosition, CommentThread, CommentThreadOptions, CommentThreadStatus, PullRequestComment,
+    ThreadContext, Threads,
+};
+
+mod git_pull_request {
+    use std::collections::HashMap;
 
-mod pr_get_response {
-    // use super::pr_refs::LinksRefs;
+    use chrono::{DateTime, Utc};
     use serde::*;
 
-    #[derive(Debug, Deserialize)]
-    pub struct PullRequestResponse {
-        // #[serde(flatten)]
-        // pub repository: Repository,
-        #[serde(rename = "pullRequestId")]
-        pub pull_request_id: i64,
-        // #[serde(rename = "codeReviewId")]
-        // pub code_review_id: Option<i64>,
-        // pub status: Option<String>,
-        #[serde(rename = "createdBy")]
-        pub created_by: CreatedBy,
-        // #[serde(rename = "creationDate")]
-        // pub creation_date: String,
-        // pub title: String,
-        // pub description: Option<String>,
-        // #[serde(rename = "sourceRefName")]
-        // pub source_ref_name: String,
-        // #[serde(rename = "targetRefName")]
-        // pub target_ref_name: String,
-        // #[serde(rename = "mergeStatus")]
-        // pub merge_status: String,
-        // #[serde(rename = "isDraft")]
-        // pub is_draft: bool,
-        // #[serde(rename = "mergeId")]
-        // pub merge_id: String,
-        // #[serde(rename = "lastMergeSourceCommit")]
-        // pub last_merge_source_commit: LastMergeSourceCommit,
-        // #[serde(rename = "lastMergeTargetCommit")]
-        // pub last_merge_target_commit: LastMergeTargetCommit,
-        // #[serde(rename = "lastMergeCommit")]
-        // pub last_merge_commit: LastMergeCommit,
-        // pub reviewers: Vec<::serde_json::Value>,
-        // pub url: String,
-        // #[serde(rename = "_links")]
-        // pub links: LinksRefs,
-        // #[serde(rename = "supportsIterations")]
-        // pub supports_iterations: bool,
-        // #[serde(rename = "artifactId")]
-        // pub artifact_id: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct Repository {
-        pub id: String,
-        pub name: String,
-        pub url: String,
-        pub project: Project,
-        pub size: i64,
-        #[serde(rename = "remoteUrl")]
-        pub remote_url: String,
-        #[serde(rename = "sshUrl")]
-        pub ssh_url: String,
-        #[serde(rename = "webUrl")]
-        pub web_url: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct Project {
-        pub id: String,
-        pub name: String,
-        pub url: String,
-        pub state: String,
-        pub revision: i64,
-        pub visibility: String,
-        #[serde(rename = "lastUpdateTime")]
-        pub last_update_time: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct CreatedBy {
-        #[serde(rename = "displayName")]
-        pub display_name: String,
-        pub url: String,
-        // #[serde(rename = "_links")]
-        // pub links: Links,
-        pub id: String,
-        #[serde(rename = "uniqueName")]
-        pub unique_name: String,
-        #[serde(rename = "imageUrl")]
-        pub image_url: String,
-        pub descriptor: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct Links {
-        pub avatar: Avatar,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct Avatar {
-        pub href: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct LastMergeSourceCommit {
-        #[serde(rename = "commitId")]
-        pub commit_id: String,
-        pub url: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct LastMergeTargetCommit {
-        #[serde(rename = "commitId")]
-        pub commit_id: String,
-        pub url: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct LastMergeCommit {
-        #[serde(rename = "commitId")]
-        pub commit_id: String,
-        pub author: Author,
-        pub committer: Committer,
-        pub comment: String,
-        pub url: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct Author {
-        pub name: String,
-        pub email: String,
-        pub date: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct Committer {
-        pub name: String,
-        pub email: String,
-        pub date: String,
-    }
-}
-
-mod pr_create_response {
-    use super::pr_refs::LinksRefs;
-    use serde::*;
+    use super::{CompletionOptions, IdentityRefWithVote, MergeStatus, PullStatus};
+    use crate::commits::GitChange;
+    use crate::identity::{Href, IdentityRef};
 
-    #[derive(Debug, Deserialize)]
-    pub struct PullRequestCreateResponse {
-        pub repository: Repository,
-        #[serde(rename = "pullRequestId")]
-        pub pull_request_id: i64,
-        #[serde(rename = "codeReviewId")]
-        pub code_review_id: i64,
-        pub status: String,
-        #[serde(rename = "createdBy")]
-        pub created_by: CreatedBy,
-        #[serde(rename = "creationDate")]
-        pub creation_date: String,
-        pub title: String,
-        pub description: String,
-        #[serde(rename = "sourceRefName")]
-        pub source_ref_name: String,
-        #[serde(rename = "targetRefName")]
-        pub target_ref_name: String,
-        #[serde(rename = "mergeStatus")]
-        pub merge_status: String,
-        #[serde(rename = "isDraft")]
+    /// A pull request, as returned by every pull request endpoint
+    ///
+    /// https://docs.microsoft.com/en-us/rest/api/azure/devops/git/pull%20requests/get%20pull%20request?view=azure-devops-rest-5.1#gitpullrequest
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GitPullRequest {
+        pub pull_request_id: u64,
+        pub code_review_id: Option<u64>,
+        pub repository: Option<GitRepositoryRef>,
+        pub status: Option<PullStatus>,
+        pub created_by: Option<IdentityRef>,
+        pub creation_date: Option<DateTime<Utc>>,
+        pub closed_by: Option<IdentityRef>,
+        pub closed_date: Option<DateTime<Utc>>,
+        pub title: Option<String>,
+        pub description: Option<String>,
+        pub source_ref_name: Option<String>,
+        pub target_ref_name: Option<String>,
+        pub merge_status: Option<MergeStatus>,
+        pub merge_failure_message: Option<String>,
+        #[serde(default)]
         pub is_draft: bool,
-        #[serde(rename = "mergeId")]
-        pub merge_id: String,
-        #[serde(rename = "lastMergeSourceCommit")]
-        pub last_merge_source_commit: LastMergeSourceCommit,
-        #[serde(rename = "lastMergeTargetCommit")]
-        pub last_merge_target_commit: LastMergeTargetCommit,
-        pub reviewers: Vec<::serde_json::Value>,
-        pub labels: Vec<::serde_json::Value>,
-        pub url: String,
-        #[serde(rename = "_links")]
-        pub links: LinksRefs,
-        #[serde(rename = "supportsIterations")]
+        pub merge_id: Option<String>,
+        pub last_merge_source_commit: Option<GitCommitRef>,
+        pub last_merge_target_commit: Option<GitCommitRef>,
+        pub last_merge_commit: Option<GitCommitRef>,
+        #[serde(default)]
+        pub reviewers: Vec<IdentityRefWithVote>,
+        #[serde(default)]
+        pub labels: Vec<WebApiTagDefinition>,
+        pub auto_complete_set_by: Option<IdentityRef>,
+        pub completion_options: Option<CompletionOptions>,
+        pub completion_queue_time: Option<DateTime<Utc>>,
+        #[serde(default)]
         pub supports_iterations: bool,
-        #[serde(rename = "artifactId")]
-        pub artifact_id: String,
+        /// identifies the pull request in policy evaluations, `vstfs:///CodeReview/CodeReviewId/{projectId}/{pullRequestId}`
+        pub artifact_id: Option<String>,
+        pub url: Option<String>,
+        #[serde(rename = "_links", default)]
+        pub links: HashMap<String, Href>,
     }
 
-    #[derive(Debug, Deserialize)]
-    pub struct Repository {
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GitRepositoryRef {
         pub id: String,
         pub name: String,
-        pub url: String,
-        pub project: Project,
-        pub size: i64,
-        #[serde(rename = "remoteUrl")]
-        pub remote_url: String,
-        #[serde(rename = "sshUrl")]
-        pub ssh_url: String,
-        #[serde(rename = "webUrl")]
-        pub web_url: String,
+        pub url: Option<String>,
+        pub project: Option<TeamProjectReference>,
+        pub remote_url: Option<String>,
+        pub ssh_url: Option<String>,
+        pub web_url: Option<String>,
     }
 
-    #[derive(Debug, Deserialize)]
-    pub struct Project {
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TeamProjectReference {
         pub id: String,
         pub name: String,
-        pub url: String,
-        pub state: String,
-        pub revision: i64,
-        pub visibility: String,
-        #[serde(rename = "lastUpdateTime")]
-        pub last_update_time: String,
-    }
-
-    #[derive(Debug, Deserialize)]
-    pub struct CreatedBy {
-        #[serde(rename = "displayName")]
-        pub display_name: String,
-        pub url: String,
-        #[serde(rename = "_links")]
-        pub links: Links,
-        pub id: String,
-        #[serde(rename = "uniqueName")]
-        pub unique_name: String,
-        #[serde(rename = "imageUrl")]
-        pub image_url: String,
-        pub descriptor: String,
-    }
-
-    #[derive(Debug, Deserialize)]
-    pub struct Links {
-        pub avatar: Avatar,
-    }
-
-    #[derive(Debug, Deserialize)]
-    pub struct Avatar {
-        pub href: String,
+        pub description: Option<String>,
+        pub url: Option<String>,
+        pub state: Option<String>,
+        pub revision: Option<u64>,
+        pub visibility: Option<String>,
+        pub last_update_time: Option<DateTime<Utc>>,
     }
 
-    #[derive(Debug, Deserialize)]
-    pub struct LastMergeSourceCommit {
-        #[serde(rename = "commitId")]
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GitCommitRef {
         pub commit_id: String,
-        pub url: String,
+        pub author: Option<GitUserDate>,
+        pub committer: Option<GitUserDate>,
+        pub comment: Option<String>,
+        #[serde(default)]
+        pub parents: Vec<String>,
+        /// number of changes by kind, e.g. `Edit`
+        #[serde(default)]
+        pub change_counts: HashMap<String, u32>,
+        /// changes of the commit, see `Commits::get`
+        #[serde(default)]
+        pub changes: Vec<GitChange>,
+        #[serde(default)]
+        pub work_items: Vec<ResourceRef>,
+        pub remote_url: Option<String>,
+        pub url: Option<String>,
+    }
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    pub struct GitUserDate {
+        pub name: String,
+        pub email: String,
+        pub date: Option<DateTime<Utc>>,
     }
 
-    #[derive(Debug, Deserialize)]
-    pub struct LastMergeTargetCommit {
-        #[serde(rename = "commitId")]
-        pub commit_id: String,
-        pub url: String,
+    /// Reference to a resource, e.g. a work item linked to a pull request
+    #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+    pub struct ResourceRef {
+        pub id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub url: Option<String>,
     }
-}
 
-mod pr_refs {
-    use serde::*;
-    #[derive(Debug, Deserialize)]
-    pub struct LinksRefs {
-        #[serde(rename = "self")]
-        pub self_field: Href,
-        pub repository: Option<RepositoryRef>,
-        #[serde(rename = "workItems")]
-        pub work_items: Option<WorkItemsRef>,
-        #[serde(rename = "sourceBranch")]
-        pub source_branch: Option<SourceBranchRef>,
-        #[serde(rename = "targetBranch")]
-        pub target_branch: Option<TargetBranchRef>,
-        pub statuses: Option<StatusesRef>,
-        #[serde(rename = "sourceCommit")]
-        pub source_commit: Option<SourceCommitRef>,
-        #[serde(rename = "targetCommit")]
-        pub target_commit: Option<TargetCommitRef>,
-        #[serde(rename = "createdBy")]
-        pub created_by: Option<CreatedByRef>,
-        pub iterations: Option<IterationsRef>,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct Href {
-        pub href: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct RepositoryRef {
-        pub href: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct WorkItemsRef {
-        pub href: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct SourceBranchRef {
-        pub href: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct TargetBranchRef {
-        pub href: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct StatusesRef {
-        pub href: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct SourceCommitRef {
-        pub href: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct TargetCommitRef {
-        pub href: String,
-    }
-    #[derive(Debug, Deserialize)]
-    pub struct CreatedByRef {
-        pub href: String,
+    impl ResourceRef {
+        /// reference to the work item with the given id
+        pub fn work_item(id: usize) -> Self {
+            ResourceRef {
+                id: id.to_string(),
+                url: None,
+            }
+        }
     }
-    #[derive(Debug, Deserialize)]
-    pub struct IterationsRef {
-        pub href: String,
+
+    /// A label of a pull request
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    pub struct WebApiTagDefinition {
+        pub id: String,
+        pub name: String,
+        pub active: Option<bool>,
+        pub url: Option<String>,
     }
 }
 
 mod pull_request_list_options {
 
-    use super::PullStatus;
+    use super::{PullStatus, QueryTimeRangeType};
+    use chrono::{DateTime, SecondsFormat, Utc};
     use std::collections::HashMap;
     use url::form_urlencoded;
-    #[derive(Default)]
+    #[derive(Clone, Default)]
     pub struct PullListOptions {
         pub params: HashMap<&'static str, String>,
     }
@@ -348,12 +198,12 @@ mod pull_request_list_options {
 
     impl PullListOptionsBuilder {
         pub fn skip(&mut self, skip: u32) -> &mut Self {
-            self.0.params.insert("skip", skip.to_string());
+            self.0.params.insert("$skip", skip.to_string());
             self
         }
 
         pub fn top(&mut self, top: u32) -> &mut Self {
-            self.0.params.insert("top", top.to_string());
+            self.0.params.insert("$top", top.to_string());
             self
         }
 
@@ -428,6 +278,42 @@ mod pull_request_list_options {
             self
         }
 
+        pub fn creator_id<C>(&mut self, creator_id: C) -> &mut Self
+        where
+            C: Into<String>,
+        {
+            self.0
+                .params
+                .insert("searchCriteria.creatorId", creator_id.into());
+            self
+        }
+
+        /// only pull requests created, or closed, after `min_time`, see `query_time_range_type`
+        pub fn min_time(&mut self, min_time: DateTime<Utc>) -> &mut Self {
+            self.0.params.insert(
+                "searchCriteria.minTime",
+                min_time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            );
+            self
+        }
+
+        /// only pull requests created, or closed, before `max_time`, see `query_time_range_type`
+        pub fn max_time(&mut self, max_time: DateTime<Utc>) -> &mut Self {
+            self.0.params.insert(
+                "searchCriteria.maxTime",
+                max_time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            );
+            self
+        }
+
+        /// the date `min_time` and `max_time` apply to, the creation date by default
+        pub fn query_time_range_type(&mut self, range_type: QueryTimeRangeType) -> &mut Self {
+            self.0
+                .params
+                .insert("searchCriteria.queryTimeRangeType", range_type.to_string());
+            self
+        }
+
         pub fn build(&self) -> PullListOptions {
             PullListOptions {
                 params: self.0.params.clone(),
@@ -436,118 +322,39 @@ mod pull_request_list_options {
     }
 }
 
-mod pull_requests_reponse {
-    use serde::*;
-    #[derive(Debug, Deserialize)]
-    pub struct PullRequestsResponse {
-        pub value: Vec<Value>,
-        pub count: i64,
-    }
-
-    #[derive(Debug, Deserialize)]
-    pub struct Value {
-        pub repository: Repository,
-        #[serde(rename = "pullRequestId")]
-        pub pull_request_id: u64,
-        #[serde(rename = "codeReviewId")]
-        pub code_review_id: i64,
-        pub status: String,
-        #[serde(rename = "createdBy")]
-        pub created_by: CreatedBy,
-        #[serde(rename = "creationDate")]
-        pub creation_date: String,
-        pub title: String,
-        pub description: String,
-        #[serde(rename = "sourceRefName")]
-        pub source_ref_name: String,
-        #[serde(rename = "targetRefName")]
-        pub target_ref_name: String,
-        #[serde(rename = "mergeStatus")]
-        pub merge_status: String,
-        #[serde(rename = "isDraft")]
-        pub is_draft: bool,
-        #[serde(rename = "mergeId")]
-        pub merge_id: String,
-        #[serde(rename = "lastMergeSourceCommit")]
-        pub last_merge_source_commit: LastMergeSourceCommit,
-        #[serde(rename = "lastMergeTargetCommit")]
-        pub last_merge_target_commit: LastMergeTargetCommit,
-        #[serde(rename = "lastMergeCommit")]
-        pub last_merge_commit: LastMergeCommit,
-        pub reviewers: Vec<::serde_json::Value>,
-        pub url: String,
-        #[serde(rename = "supportsIterations")]
-        pub supports_iterations: bool,
-    }
-
-    #[derive(Debug, Deserialize)]
-    pub struct Repository {
-        pub id: String,
-        pub name: String,
-        pub url: String,
-        pub project: Project,
-    }
-
-    #[derive(Debug, Deserialize)]
-    pub struct Project {
-        pub id: String,
-        pub name: String,
-        pub state: String,
-        pub visibility: String,
-        #[serde(rename = "lastUpdateTime")]
-        pub last_update_time: String,
-    }
-
-    #[derive(Debug, Deserialize)]
-    pub struct CreatedBy {
-        #[serde(rename = "displayName")]
-        pub display_name: String,
-        pub url: String,
-        #[serde(rename = "_links")]
-        pub links: Links,
-        pub id: String,
-        #[serde(rename = "uniqueName")]
-        pub unique_name: String,
-        #[serde(rename = "imageUrl")]
-        pub image_url: String,
-        pub descriptor: String,
-    }
-
-    #[derive(Debug, Deserialize)]
-    pub struct Links {
-        pub avatar: Avatar,
-    }
+mod pull_request_properties {
+    use std::collections::HashMap;
 
-    #[derive(Debug, Deserialize)]
-    pub struct Avatar {
-        pub href: String,
-    }
+    use serde::*;
+    use serde_json::Value;
 
+    /// Properties of a pull request, along with their types
     #[derive(Debug, Deserialize)]
-    pub struct LastMergeSourceCommit {
-        #[serde(rename = "commitId")]
-        pub commit_id: String,
-        pub url: String,
+    pub(crate) struct PropertiesCollection {
+        #[serde(default)]
+        pub(crate) value: HashMap<String, PropertyValue>,
     }
 
     #[derive(Debug, Deserialize)]
-    pub struct LastMergeTargetCommit {
-        #[serde(rename = "commitId")]
-        pub commit_id: String,
-        pub url: String,
+    pub(crate) struct PropertyValue {
+        #[serde(rename = "$value")]
+        pub(crate) value: Value,
     }
 
-    #[derive(Debug, Deserialize)]
-    pub struct LastMergeCommit {
-        #[serde(rename = "commitId")]
-        pub commit_id: String,
-        pub url: String,
+    impl PropertiesCollection {
+        pub(crate) fn into_values(self) -> HashMap<String, Value> {
+            self.value
+                .into_iter()
+                .map(|(key, property)| (key, property.value))
+                .collect()
+        }
     }
 }
 
 mod pr_create_options {
+    use super::ResourceRef;
     use serde::*;
-    #[derive(Debug, Serialize)]
+    #[derive(Debug, Default, Serialize)]
     pub struct PROption {
         #[serde(rename = "sourceRefName")]
         pub source_ref_name: String,
@@ -558,6 +365,10 @@ mod pr_create_options {
         pub description: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub reviewers: Option<Vec<Reviewer>>,
+        /// work items to link the pull request to
+        #[serde(rename = "workItemRefs")]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub work_item_refs: Option<Vec<ResourceRef>>,
     }
 
     #[derive(Debug, Serialize)]
@@ -579,15 +390,15 @@ mod pull_update_options {
         #[serde(skip_serializing_if = "Option::is_none")]
         status: Option<PullStatus>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        #[serde(rename = "mergeStrategy")]
-        merge_strategy: Option<MergeStrategy>,
-        #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "lastMergeSourceCommit")]
         source_commit: Option<LastMergeSourceCommit>,
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "completionOptions")]
         completion_options: Option<CompletionOptions>,
         #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "autoCompleteSetBy")]
+        auto_complete_set_by: Option<AutoCompleteSetBy>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "isDraft")]
         draft:Option<bool>
     }
@@ -595,20 +406,87 @@ mod pull_update_options {
     #[derive(Debug, Default, Serialize, Clone)]
     pub struct LastMergeSourceCommit {
         #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "commitId")]
         commit_id: Option<String>,
     }
 
     #[derive(Debug, Default, Serialize, Clone)]
+    pub struct AutoCompleteSetBy {
+        id: String,
+    }
+
+    /// Options the pull request is completed with, see `PullRequest::complete`
+    #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
     pub struct CompletionOptions {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "mergeStrategy")]
+        pub merge_strategy: Option<MergeStrategy>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "mergeCommitMessage")]
+        pub merge_commit_message: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "bypassPolicy")]
-        bypass_policy: Option<bool>,
+        pub bypass_policy: Option<bool>,
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "bypassReason")]
-        bypass_reason: Option<String>,
+        pub bypass_reason: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "deleteSourceBranch")]
-        delete_source_branch: Option<bool>,
+        pub delete_source_branch: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "transitionWorkItems")]
+        pub transition_work_items: Option<bool>,
+    }
+
+    impl CompletionOptions {
+        pub fn builder() -> CompletionOptionsBuilder {
+            CompletionOptionsBuilder::default()
+        }
+    }
+
+    #[derive(Default)]
+    pub struct CompletionOptionsBuilder(CompletionOptions);
+
+    impl CompletionOptionsBuilder {
+        /// set the merge strategy of the pull
+        pub fn merge_strategy(&mut self, merge_strategy: MergeStrategy) -> &mut Self {
+            self.0.merge_strategy = Some(merge_strategy);
+            self
+        }
+
+        /// set the message of the merge or squash commit
+        pub fn merge_commit_message<M>(&mut self, merge_commit_message: M) -> &mut Self
+        where
+            M: Into<String>,
+        {
+            self.0.merge_commit_message = Some(merge_commit_message.into());
+            self
+        }
+
+        /// complete the pull even if policies aren't met, requires the bypass permission
+        pub fn bypass_policy<B>(&mut self, bypass_reason: B) -> &mut Self
+        where
+            B: Into<String>,
+        {
+            self.0.bypass_policy = Some(true);
+            self.0.bypass_reason = Some(bypass_reason.into());
+            self
+        }
+
+        pub fn delete_source_branch(&mut self, delete_source_branch: bool) -> &mut Self {
+            self.0.delete_source_branch = Some(delete_source_branch);
+            self
+        }
+
+        /// move the linked work items to their next state
+        pub fn transition_work_items(&mut self, transition_work_items: bool) -> &mut Self {
+            self.0.transition_work_items = Some(transition_work_items);
+            self
+        }
+
+        pub fn build(&self) -> CompletionOptions {
+            self.0.clone()
+        }
     }
 
     impl PullUpdateOptions {
@@ -646,7 +524,7 @@ mod pull_update_options {
 
         /// set the merge strategy of the pull
         pub fn merge_strategy(&mut self, merge_strategy: MergeStrategy) -> &mut Self {
-            self.0.merge_strategy = Some(merge_strategy.into());
+            self.completion().merge_strategy = Some(merge_strategy);
             self
         }
 
@@ -654,34 +532,58 @@ mod pull_update_options {
             self.0.draft = Some(draft);
             self
         }
-        // /// set the source commit of the merge
-        // pub fn source_commit<C>(&mut self, source_commit: C) -> &mut Self
-        // where
-        //     C: Into<String>,
-        // {
-        //     self.0.source_commit = LastMergeSourceCommit {
-        //         commit_id: Some(source_commit.into()),
-        //     };
-        //     self
-        // }
-
-        // pub fn bypass_policy(&mut self, bypass_policy: bool) -> &mut Self {
-        //     self.0.completion_options.bypass_policy = Some(bypass_policy);
-        //     self
-        // }
-
-        // /// set the bypass reason of the pull
-        // pub fn bypass_reason<B>(&mut self, bypass_reason: B) -> &mut Self
-        // where
-        //     B: Into<String>,
-        // {
-        //     self.0.completion_options.bypass_reason = Some(bypass_reason.into());
-        //     self
-        // }
-        // pub fn delete_source_branch(&mut self, delete_source_branch: bool) -> &mut Self {
-        //     self.0.completion_options.delete_source_branch = Some(delete_source_branch);
-        //     self
-        // }
+        /// set the source commit of the merge
+        pub fn source_commit<C>(&mut self, source_commit: C) -> &mut Self
+        where
+            C: Into<String>,
+        {
+            self.0.source_commit = Some(LastMergeSourceCommit {
+                commit_id: Some(source_commit.into()),
+            });
+            self
+        }
+
+        pub fn bypass_policy(&mut self, bypass_policy: bool) -> &mut Self {
+            self.completion().bypass_policy = Some(bypass_policy);
+            self
+        }
+
+        /// set the bypass reason of the pull
+        pub fn bypass_reason<B>(&mut self, bypass_reason: B) -> &mut Self
+        where
+            B: Into<String>,
+        {
+            self.completion().bypass_reason = Some(bypass_reason.into());
+            self
+        }
+        pub fn delete_source_branch(&mut self, delete_source_branch: bool) -> &mut Self {
+            self.completion().delete_source_branch = Some(delete_source_branch);
+            self
+        }
+
+        /// set every completion option at once
+        pub fn completion_options(&mut self, completion_options: CompletionOptions) -> &mut Self {
+            self.0.completion_options = Some(completion_options);
+            self
+        }
+
+        /// set the identity the pull will be completed on behalf of once policies are met,
+        /// the empty guid cancelling auto complete
+        pub fn auto_complete_set_by<I>(&mut self, identity_id: I) -> &mut Self
+        where
+            I: Into<String>,
+        {
+            self.0.auto_complete_set_by = Some(AutoCompleteSetBy {
+                id: identity_id.into(),
+            });
+            self
+        }
+
+        fn completion(&mut self) -> &mut CompletionOptions {
+            self.0
+                .completion_options
+                .get_or_insert_with(Default::default)
+        }
 
         /// create a new set of pull edit options
         pub fn build(&self) -> PullUpdateOptions {
@@ -690,15 +592,16 @@ mod pull_update_options {
                 description: self.0.description.clone(),
                 status: self.0.status.clone(),
                 draft: self.0.draft.clone(),
-                merge_strategy: self.0.merge_strategy.clone(),
                 source_commit: self.0.source_commit.clone(),
                 completion_options: self.0.completion_options.clone(),
+                auto_complete_set_by: self.0.auto_complete_set_by.clone(),
             }
         }
     }
 }
 /// enum representation of Azure Pull Request Status
-#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum PullStatus {
     Abandoned,
     Active,
@@ -729,7 +632,8 @@ impl Default for PullStatus {
 }
 
 /// enum representation of Azure Pull Request Merge Strategies, by default will squash merge
-#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum MergeStrategy {
     NoFastForward,
     Rebase,
@@ -755,3 +659,46 @@ impl Default for MergeStrategy {
         MergeStrategy::Squash
     }
 }
+
+/// enum representation of the outcome of the last merge attempt of a pull request
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeStatus {
+    NotSet,
+    Queued,
+    Conflicts,
+    Succeeded,
+    RejectedByPolicy,
+    Failure,
+}
+
+impl fmt::Display for MergeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            MergeStatus::NotSet => "notSet",
+            MergeStatus::Queued => "queued",
+            MergeStatus::Conflicts => "conflicts",
+            MergeStatus::Succeeded => "succeeded",
+            MergeStatus::RejectedByPolicy => "rejectedByPolicy",
+            MergeStatus::Failure => "failure",
+        }
+        .fmt(f)
+    }
+}
+
+/// enum representation of the date pull request searches filter on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QueryTimeRangeType {
+    Created,
+    Closed,
+}
+
+impl fmt::Display for QueryTimeRangeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            QueryTimeRangeType::Created => "created",
+            QueryTimeRangeType::Closed => "closed",
+        }
+        .fmt(f)
+    }
+}
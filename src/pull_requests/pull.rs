@@ -1,5 +1,26 @@
-use super::{PullRequestResponse, PullStatus, PullUpdateOptions};
-use crate::{AzureClient, Future};
+use std::collections::{BTreeMap, HashMap};
+
+use futures::{FutureExt, TryFutureExt};
+use http::StatusCode;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::Value;
+
+use super::iterations::GitPullRequestIterationChanges;
+use super::pull_request_properties::PropertiesCollection;
+use super::reviewers::ReviewerOptions;
+use super::{
+    ChangeType, CompletionOptions, GitPullRequest, GitPullRequestChange, GitPullRequestIteration,
+    GitPullRequestStatus, GitPullRequestStatusOptions, IdentityRefWithVote, PullStatus,
+    PullUpdateOptions, ResourceRef, Threads, Vote, WebApiTagDefinition,
+};
+use crate::errors::{Error, ErrorKind};
+use crate::json_patch::JsonPatch;
+use crate::policies::{Policies, PolicyEvaluationRecord};
+use crate::work_items::{Artifact, WorkItem};
+use crate::{AzureClient, Future, MediaType};
+
+/// number of changes requested per page, the server may answer with less
+const CHANGES_PAGE_SIZE: u32 = 100;
 
 pub struct PullRequest {
     ops: AzureClient,
@@ -31,25 +52,551 @@ impl PullRequest {
         )
     }
     /// Request a pull requests information
-    pub fn get(&self) -> Future<PullRequestResponse> {
+    pub fn get(&self) -> Future<GitPullRequest> {
         self.ops.get(&self.path(""))
     }
 
     /// Update a pull request
-    pub fn update(&self, pr: &PullUpdateOptions) -> Future<PullRequestResponse> {
+    pub fn update(&self, pr: &PullUpdateOptions) -> Future<GitPullRequest> {
         let body = json!(pr);
-        self.ops
-            .patch::<PullRequestResponse>(&self.path("?"), body)
+        self.ops.patch::<GitPullRequest>(&self.path("?"), body)
     }
 
     /// short hand for updating pr status = active
-    pub fn active(&self) -> Future<PullRequestResponse> {
+    pub fn active(&self) -> Future<GitPullRequest> {
         self.update(&PullUpdateOptions::builder().status(PullStatus::Active).build())
     }
 
     /// short hand for updating pr status = abandoned
-    pub fn abandon(&self) -> Future<PullRequestResponse> {
+    pub fn abandon(&self) -> Future<GitPullRequest> {
         self.update(&PullUpdateOptions::builder().status(PullStatus::Abandoned).build())
     }
 
+    /// Complete the pull request
+    ///
+    /// Azure only merges the source commit it was told about, so the pull request is fetched
+    /// first to get its current `lastMergeSourceCommit`.
+    pub fn complete(&self, options: &CompletionOptions) -> Future<GitPullRequest> {
+        let pull = PullRequest::new(
+            self.ops.clone(),
+            self.project.as_str(),
+            self.repo.as_str(),
+            self.id,
+        );
+        let current = self.get();
+        let options = options.clone();
+        Box::pin(async move {
+            let mut update = PullUpdateOptions::builder();
+            update
+                .status(PullStatus::Completed)
+                .completion_options(options);
+            if let Some(commit) = current.await?.last_merge_source_commit {
+                update.source_commit(commit.commit_id);
+            }
+            pull.update(&update.build()).await
+        })
+    }
+
+    /// Complete the pull request on behalf of `identity_id` as soon as its policies are met
+    pub fn set_auto_complete(
+        &self,
+        identity_id: &str,
+        options: &CompletionOptions,
+    ) -> Future<GitPullRequest> {
+        self.update(
+            &PullUpdateOptions::builder()
+                .auto_complete_set_by(identity_id)
+                .completion_options(options.clone())
+                .build(),
+        )
+    }
+
+    /// short hand for cancelling auto complete
+    pub fn cancel_auto_complete(&self) -> Future<GitPullRequest> {
+        self.update(
+            &PullUpdateOptions::builder()
+                .auto_complete_set_by("00000000-0000-0000-0000-000000000000")
+                .build(),
+        )
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/reviewers?api-version=5.1
+    pub fn reviewers(&self) -> Future<Vec<IdentityRefWithVote>> {
+        self.ops.get_values(&self.path("/reviewers"))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/reviewers/{reviewerId}?api-version=5.1
+    pub fn reviewer(&self, reviewer_id: &str) -> Future<IdentityRefWithVote> {
+        self.ops
+            .get(&self.path(&format!("/reviewers/{}", reviewer_id)))
+    }
+
+    /// Add a reviewer, optional unless `required`, keeping their vote if they already review
+    ///
+    /// PUT https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/reviewers/{reviewerId}?api-version=5.1
+    pub fn add_reviewer(&self, reviewer_id: &str, required: bool) -> Future<IdentityRefWithVote> {
+        self.update_reviewer(
+            reviewer_id,
+            self.existing_reviewer(reviewer_id),
+            None,
+            Some(required),
+        )
+    }
+
+    /// Make an existing reviewer required or optional, keeping their vote
+    pub fn set_reviewer_required(
+        &self,
+        reviewer_id: &str,
+        required: bool,
+    ) -> Future<IdentityRefWithVote> {
+        let reviewer = Box::pin(self.reviewer(reviewer_id).map_ok(Some));
+        self.update_reviewer(reviewer_id, reviewer, None, Some(required))
+    }
+
+    /// DELETE https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/reviewers/{reviewerId}?api-version=5.1
+    pub fn remove_reviewer(&self, reviewer_id: &str) -> Future<Option<serde_json::Value>> {
+        self.ops
+            .delete(&self.path(&format!("/reviewers/{}", reviewer_id)))
+    }
+
+    /// Cast a vote, `reviewer_id` being the id of the authenticated user
+    ///
+    /// PUT https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/reviewers/{reviewerId}?api-version=5.1
+    pub fn vote(&self, reviewer_id: &str, vote: Vote) -> Future<IdentityRefWithVote> {
+        self.update_reviewer(
+            reviewer_id,
+            self.existing_reviewer(reviewer_id),
+            Some(vote),
+            None,
+        )
+    }
+
+    /// short hand for voting approve
+    pub fn approve(&self, reviewer_id: &str) -> Future<IdentityRefWithVote> {
+        self.vote(reviewer_id, Vote::Approve)
+    }
+
+    /// the reviewer, or `None` if they do not review the pull request yet
+    fn existing_reviewer(&self, reviewer_id: &str) -> Future<Option<IdentityRefWithVote>> {
+        Box::pin(self.reviewer(reviewer_id).then(|reviewer| async move {
+            match reviewer {
+                Ok(reviewer) => Ok(Some(reviewer)),
+                Err(Error(ErrorKind::Fault { code, .. }, _)) if code == StatusCode::NOT_FOUND => {
+                    Ok(None)
+                }
+                Err(err) => Err(err),
+            }
+        }))
+    }
+
+    /// A reviewer is replaced by a PUT, so what `vote` and `is_required` leave unset is taken from
+    /// the existing `reviewer`
+    fn update_reviewer(
+        &self,
+        reviewer_id: &str,
+        reviewer: Future<Option<IdentityRefWithVote>>,
+        vote: Option<Vote>,
+        is_required: Option<bool>,
+    ) -> Future<IdentityRefWithVote> {
+        let ops = self.ops.clone();
+        let uri = self.path(&format!("/reviewers/{}", reviewer_id));
+        Box::pin(async move {
+            let reviewer = reviewer.await?;
+            let options = ReviewerOptions {
+                vote: vote.or_else(|| reviewer.as_ref().map(|reviewer| reviewer.vote)),
+                is_required: is_required
+                    .or_else(|| reviewer.as_ref().map(|reviewer| reviewer.is_required)),
+            };
+            put_reviewer(&ops, &uri, &options).await
+        })
+    }
+
+    /// Iterations of the pull request, one per push to its source branch
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/iterations?includeCommits={includeCommits}&api-version=5.1
+    pub fn iterations(&self, include_commits: bool) -> Future<Vec<GitPullRequestIteration>> {
+        self.ops
+            .get_values(&self.path(&format!("/iterations?includeCommits={}", include_commits)))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/iterations/{iterationId}?api-version=5.1
+    pub fn iteration(&self, iteration_id: u32) -> Future<GitPullRequestIteration> {
+        self.ops
+            .get(&self.path(&format!("/iterations/{}", iteration_id)))
+    }
+
+    /// Changes of an iteration, compared to the iteration `compare_to` or to the target branch,
+    /// requesting pages until exhaustion
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/iterations/{iterationId}/changes?$top={$top}&$skip={$skip}&$compareTo={$compareTo}&api-version=5.1
+    pub fn iteration_changes(
+        &self,
+        iteration_id: u32,
+        compare_to: Option<u32>,
+    ) -> Future<Vec<GitPullRequestChange>> {
+        let ops = self.ops.clone();
+        let mut uri = self.path(&format!("/iterations/{}/changes?", iteration_id));
+        if let Some(compare_to) = compare_to {
+            uri.push_str(&format!("$compareTo={}&", compare_to));
+        }
+        Box::pin(async move {
+            let mut changes = Vec::new();
+            let (mut top, mut skip) = (CHANGES_PAGE_SIZE, 0);
+            loop {
+                let page: GitPullRequestIterationChanges = ops
+                    .get(&format!("{}$top={}&$skip={}", uri, top, skip))
+                    .await?;
+                changes.extend(page.change_entries);
+                if page.next_skip <= skip {
+                    return Ok(changes);
+                }
+                skip = page.next_skip;
+                if page.next_top > 0 {
+                    top = page.next_top;
+                }
+            }
+        })
+    }
+
+    /// Files changed in iteration `to` since iteration `from`, or since the target branch,
+    /// along with how they changed
+    pub fn changed_files(
+        &self,
+        from: Option<u32>,
+        to: u32,
+    ) -> Future<BTreeMap<String, Vec<ChangeType>>> {
+        Box::pin(self.iteration_changes(to, from).map_ok(|changes| {
+            changes
+                .into_iter()
+                .filter(|change| !change.is_folder())
+                .filter_map(|change| {
+                    let path = change.path()?.to_owned();
+                    Some((path, change.change_types))
+                })
+                .collect()
+        }))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/statuses?api-version=5.1-preview.1
+    pub fn statuses(&self) -> Future<Vec<GitPullRequestStatus>> {
+        self.ops.get_values(&self.statuses_path(""))
+    }
+
+    /// Report the outcome of a check, e.g. a build
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/statuses?api-version=5.1-preview.1
+    pub fn create_status(
+        &self,
+        status: &GitPullRequestStatusOptions,
+    ) -> Future<GitPullRequestStatus> {
+        self.ops.post(&self.statuses_path(""), json!(status))
+    }
+
+    /// DELETE https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/statuses/{statusId}?api-version=5.1-preview.1
+    pub fn delete_status(&self, status_id: u64) -> Future<Option<serde_json::Value>> {
+        self.ops
+            .delete(&self.statuses_path(&format!("/{}", status_id)))
+    }
+
+    /// Evaluations of the policies applying to the pull request
+    ///
+    /// Policies are evaluated against the artifact id of the pull request, so it is fetched first.
+    pub fn policy_evaluations(&self) -> Future<Vec<PolicyEvaluationRecord>> {
+        let policies = Policies::new(self.ops.clone(), self.project.as_str());
+        let id = self.id;
+        let pull = self.get();
+        Box::pin(async move {
+            let artifact_id = pull
+                .await?
+                .artifact_id
+                .ok_or_else(|| ErrorKind::Msg(format!("pull request {} has no artifact id", id)))?;
+            policies.evaluations(&artifact_id, false).await
+        })
+    }
+
+    fn statuses_path(&self, more: &str) -> String {
+        format!(
+            "{}?{}",
+            self.path(&format!("/statuses{}", more)),
+            self.ops.api_version.preview(1)
+        )
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/labels?api-version=5.1-preview.1
+    pub fn labels(&self) -> Future<Vec<WebApiTagDefinition>> {
+        self.ops.get_values(&self.labels_path(""))
+    }
+
+    /// Add a label, creating it if no pull request of the project has it yet
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/labels?api-version=5.1-preview.1
+    pub fn add_label(&self, name: &str) -> Future<WebApiTagDefinition> {
+        let body = serde_json::json!({ "name": name });
+        self.ops.post(&self.labels_path(""), json!(body))
+    }
+
+    /// DELETE https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/labels/{labelIdOrName}?api-version=5.1-preview.1
+    pub fn remove_label(&self, label: &str) -> Future<Option<Value>> {
+        let label = utf8_percent_encode(label, NON_ALPHANUMERIC);
+        self.ops.delete(&self.labels_path(&format!("/{}", label)))
+    }
+
+    /// Arbitrary metadata attached to the pull request
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/properties?api-version=5.1-preview.1
+    pub fn properties(&self) -> Future<HashMap<String, Value>> {
+        Box::pin(
+            self.ops
+                .get::<PropertiesCollection>(&self.properties_path())
+                .map_ok(PropertiesCollection::into_values),
+        )
+    }
+
+    /// Add, replace or remove properties, each path being `/{key}`
+    ///
+    /// PATCH https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/properties?api-version=5.1-preview.1
+    pub fn update_properties(&self, patch: &JsonPatch) -> Future<HashMap<String, Value>> {
+        Box::pin(
+            self.ops
+                .patch_media::<PropertiesCollection>(
+                    &self.properties_path(),
+                    json!(patch),
+                    MediaType::JsonPatch,
+                )
+                .map_ok(PropertiesCollection::into_values),
+        )
+    }
+
+    /// short hand for adding or replacing a single property
+    pub fn set_property<V>(&self, key: &str, value: V) -> Future<HashMap<String, Value>>
+    where
+        V: Into<Value>,
+    {
+        // keys are json pointers, escaped as per rfc 6901
+        let path = format!("/{}", key.replace('~', "~0").replace('/', "~1"));
+        self.update_properties(&JsonPatch::builder().add(path, value).build())
+    }
+
+    /// Work items linked to the pull request
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/workitems?api-version=5.1
+    pub fn work_items(&self) -> Future<Vec<ResourceRef>> {
+        self.ops.get_values(&self.path("/workitems"))
+    }
+
+    /// Link a work item to the pull request
+    ///
+    /// Links are stored on work items and point to the pull request through the ids of its project and
+    /// repository, so the pull request is fetched first to find them.
+    pub fn link_work_item(&self, work_item_id: usize) -> Future<WorkItem> {
+        let ops = self.ops.clone();
+        let pull = self.get();
+        Box::pin(async move {
+            let pull = pull.await?;
+            let repository = pull
+                .repository
+                .ok_or_else(|| ErrorKind::Msg("pull request has no repository".into()))?;
+            let project = repository
+                .project
+                .ok_or_else(|| ErrorKind::Msg("pull request repository has no project".into()))?;
+            let artifact = Artifact::PullRequest {
+                project_id: project.id,
+                repository_id: repository.id,
+                pull_request_id: pull.pull_request_id,
+            };
+            ops.link_artifact(work_item_id, &artifact, None).await
+        })
+    }
+
+    fn labels_path(&self, more: &str) -> String {
+        format!(
+            "{}?{}",
+            self.path(&format!("/labels{}", more)),
+            self.ops.api_version.preview(1)
+        )
+    }
+
+    fn properties_path(&self) -> String {
+        format!(
+            "{}?{}",
+            self.path("/properties"),
+            self.ops.api_version.preview(1)
+        )
+    }
+
+    /// Comment threads of the pull request
+    pub fn threads(&self) -> Threads {
+        Threads::new(
+            self.ops.clone(),
+            self.project.as_str(),
+            self.repo.as_str(),
+            self.id,
+        )
+    }
+
+}
+
+fn put_reviewer(
+    ops: &AzureClient,
+    uri: &str,
+    reviewer: &ReviewerOptions,
+) -> Future<IdentityRefWithVote> {
+    ops.put(uri, json!(reviewer))
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::pull_requests::MergeStrategy;
+
+    const PULL: &str = "/org/project/_apis/git/repositories/repo/pullrequests/3";
+
+    #[tokio::test]
+    async fn complete_with_current_source_commit() {
+        let server = MockServer::start().await;
+        let pull = serde_json::json!({
+            "pullRequestId": 3,
+            "createdBy": {
+                "displayName": "Jamie Doe",
+                "url": "https://example.com/u1",
+                "id": "u1",
+                "uniqueName": "jamie@example.com",
+                "imageUrl": "https://example.com/u1/avatar",
+                "descriptor": "aad.u1"
+            },
+            "lastMergeSourceCommit": {
+                "commitId": "b60e2f4",
+                "url": "https://example.com/commits/b60e2f4"
+            }
+        });
+        Mock::given(method("GET"))
+            .and(path(PULL))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pull.clone()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path(PULL))
+            .and(body_json(serde_json::json!({
+                "status": "completed",
+                "lastMergeSourceCommit": { "commitId": "b60e2f4" },
+                "completionOptions": {
+                    "mergeStrategy": "squash",
+                    "mergeCommitMessage": "Bump serde",
+                    "bypassPolicy": true,
+                    "bypassReason": "hotfix",
+                    "deleteSourceBranch": true,
+                    "transitionWorkItems": true
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pull))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let completed = client
+            .repo("project", "repo")
+            .pull(3)
+            .complete(
+                &CompletionOptions::builder()
+                    .merge_strategy(MergeStrategy::Squash)
+                    .merge_commit_message("Bump serde")
+                    .bypass_policy("hotfix")
+                    .delete_source_branch(true)
+                    .transition_work_items(true)
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(completed.pull_request_id, 3);
+    }
+
+    #[tokio::test]
+    async fn label_and_tag_pull_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/labels", PULL)))
+            .and(query_param("api-version", "5.1-preview.1"))
+            .and(body_json(serde_json::json!({ "name": "needs-docs" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "l1",
+                "name": "needs-docs",
+                "active": true
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path(format!("{}/properties", PULL)))
+            .and(header("content-type", "application/json-patch+json"))
+            .and(body_json(serde_json::json!([{
+                "op": "add",
+                "path": "/triage~1branch",
+                "value": "bug/42"
+            }])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 1,
+                "value": {
+                    "triage/branch": { "$type": "System.String", "$value": "bug/42" }
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let pull = client.repo("project", "repo").pull(3);
+        let label = pull.add_label("needs-docs").await.unwrap();
+        assert_eq!(label.active, Some(true));
+        let properties = pull.set_property("triage/branch", "bug/42").await.unwrap();
+        assert_eq!(properties["triage/branch"], "bug/42");
+    }
+
+    #[tokio::test]
+    async fn link_work_item_to_pull_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(PULL))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "pullRequestId": 3,
+                "repository": {
+                    "id": "r1",
+                    "name": "repo",
+                    "project": { "id": "p1", "name": "project" }
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/org/_apis/wit/workitems/42"))
+            .and(body_json(serde_json::json!([{
+                "op": "add",
+                "path": "/relations/-",
+                "value": {
+                    "rel": "ArtifactLink",
+                    "url": "vstfs:///Git/PullRequestId/p1%2Fr1%2F3",
+                    "attributes": { "name": "Pull Request" }
+                }
+            }])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 42,
+                "fields": {}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let work_item = client
+            .repo("project", "repo")
+            .pull(3)
+            .link_work_item(42)
+            .await
+            .unwrap();
+        assert_eq!(work_item.id, 42);
+    }
 }
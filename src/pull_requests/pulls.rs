@@ -1,5 +1,5 @@
-use super::{PullListOptions, PullRequestCreateResponse, PullRequestsResponse};
-use crate::{AzureClient, Future};
+use super::{GitPullRequest, PullListOptions};
+use crate::{AzureClient, Future, Pagination, Stream};
 
 pub use super::pull::PullRequest;
 pub use super::PROption;
@@ -25,7 +25,7 @@ impl PullRequests {
     }
 
     /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullrequests?api-version=5.1
-    pub fn create(&self, options: &PROption) -> Future<PullRequestCreateResponse> {
+    pub fn create(&self, options: &PROption) -> Future<GitPullRequest> {
         self.ops.post(&self.path(""), json!(options))
     }
     /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullrequests/{pullRequestId}?api-version=5.1
@@ -39,13 +39,21 @@ impl PullRequests {
     }
 
     /// list pull requests
-    pub fn list(&self, options: PullListOptions) -> Future<PullRequestsResponse> {
+    ///
+    /// Only a single page is returned, see `stream` to list every pull request
+    pub fn list(&self, options: PullListOptions) -> Future<Vec<GitPullRequest>> {
         let mut uri = vec![self.path("")];
-        for (key, value) in options.params.into_iter() {
-            uri.push(format!("{}={}", key, value))
+        if let Some(query) = options.serialize() {
+            uri.push(query);
         }
-        let uri = &uri.join("?");
-        self.ops.get::<PullRequestsResponse>(&format!("{}&", uri))
+        self.ops.get_values(&uri.join("?"))
+    }
+
+    /// Stream every pull request matching `options`, requesting pages until exhaustion
+    ///
+    /// `top` sets the page size and `skip` the number of pull requests to start from
+    pub fn stream(&self, options: PullListOptions) -> Stream<GitPullRequest> {
+        stream(&self.ops, &self.path(""), options)
     }
 
     fn path(&self, more: &str) -> String {
@@ -55,3 +63,153 @@ impl PullRequests {
         )
     }
 }
+
+/// Stream the pull requests listed at `path`, `top` setting the page size and `skip` the number of
+/// pull requests to start from
+pub(crate) fn stream(
+    ops: &AzureClient,
+    path: &str,
+    mut options: PullListOptions,
+) -> Stream<GitPullRequest> {
+    let top = options
+        .params
+        .remove("$top")
+        .and_then(|top| top.parse().ok());
+    let skip = options
+        .params
+        .remove("$skip")
+        .and_then(|skip| skip.parse().ok());
+    let mut uri = vec![path.to_owned()];
+    if let Some(query) = options.serialize() {
+        uri.push(query);
+    }
+    ops.get_stream(&uri.join("?"), Pagination::top_skip(top, skip))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use futures::TryStreamExt;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::pull_requests::{MergeStatus, PullStatus, Vote};
+
+    #[tokio::test]
+    async fn list_typed_pull_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/org/project/_apis/git/repositories/repo/pullrequests",
+            ))
+            .and(query_param("searchCriteria.status", "active"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 2,
+                "value": [{
+                    "pullRequestId": 3,
+                    "codeReviewId": 3,
+                    "repository": {
+                        "id": "r1",
+                        "name": "repo",
+                        "project": { "id": "p1", "name": "project", "state": "wellFormed" }
+                    },
+                    "status": "active",
+                    "createdBy": { "id": "u1", "displayName": "Jamie Doe" },
+                    "creationDate": "2020-05-01T10:15:30.2541239Z",
+                    "title": "Bump serde",
+                    "sourceRefName": "refs/heads/deps",
+                    "targetRefName": "refs/heads/master",
+                    "mergeStatus": "succeeded",
+                    "isDraft": false,
+                    "lastMergeSourceCommit": { "commitId": "b60e2f4" },
+                    "reviewers": [{ "id": "u2", "vote": 5 }],
+                    "labels": [{ "id": "l1", "name": "dependencies", "active": true }],
+                    "completionOptions": { "mergeStrategy": "squash", "deleteSourceBranch": true },
+                    "artifactId": "vstfs:///CodeReview/CodeReviewId/p1/3",
+                    "_links": { "self": { "href": "https://example.com/pullrequests/3" } }
+                }, {
+                    "pullRequestId": 4
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let options = PullListOptions::builder()
+            .status(PullStatus::Active)
+            .build();
+        let pulls = client
+            .repo("project", "repo")
+            .pulls()
+            .list(options)
+            .await
+            .unwrap();
+        assert_eq!(pulls[0].status, Some(PullStatus::Active));
+        assert_eq!(pulls[0].merge_status, Some(MergeStatus::Succeeded));
+        let created = Utc.with_ymd_and_hms(2020, 5, 1, 10, 15, 30).unwrap();
+        assert_eq!(
+            pulls[0].creation_date.unwrap().timestamp(),
+            created.timestamp()
+        );
+        assert_eq!(pulls[0].reviewers[0].vote, Vote::ApproveWithSuggestions);
+        let completion = pulls[0].completion_options.as_ref().unwrap();
+        assert_eq!(completion.delete_source_branch, Some(true));
+        assert_eq!(pulls[1].pull_request_id, 4);
+        assert!(pulls[1].reviewers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stream_pull_requests_awaiting_review_across_projects() {
+        let server = MockServer::start().await;
+        let project = |id: &str| {
+            serde_json::json!({
+                "id": id,
+                "name": id,
+                "url": format!("https://example.com/{}", id),
+                "state": "wellFormed",
+                "revision": 1,
+                "visibility": "private",
+                "lastUpdateTime": "2020-05-01T10:15:30.25Z"
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/projects"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 2,
+                "value": [project("alpha"), project("beta")]
+            })))
+            .mount(&server)
+            .await;
+        for (project, id) in &[("alpha", 1), ("beta", 2)] {
+            Mock::given(method("GET"))
+                .and(path(format!("/org/{}/_apis/git/pullrequests", project)))
+                .and(query_param("searchCriteria.reviewerId", "u1"))
+                .and(query_param("searchCriteria.status", "active"))
+                .and(query_param(
+                    "searchCriteria.minTime",
+                    "2020-05-01T00:00:00Z",
+                ))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "count": 1,
+                    "value": [{ "pullRequestId": id }]
+                })))
+                .mount(&server)
+                .await;
+        }
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let options = PullListOptions::builder()
+            .reviewer_id("u1")
+            .status(PullStatus::Active)
+            .min_time(Utc.with_ymd_and_hms(2020, 5, 1, 0, 0, 0).unwrap())
+            .build();
+        let pulls: Vec<GitPullRequest> = client
+            .org_pull_requests(options)
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<u64> = pulls.iter().map(|pull| pull.pull_request_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+}
@@ -0,0 +1,249 @@
+//! Pull request reviewers and their votes
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/pull%20request%20reviewers?view=azure-devops-rest-5.1
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::identity::IdentityRef;
+
+/// A reviewer of a pull request along with their vote
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityRefWithVote {
+    #[serde(flatten)]
+    pub identity: IdentityRef,
+    #[serde(default)]
+    pub vote: Vote,
+    #[serde(default)]
+    pub is_required: bool,
+    #[serde(default)]
+    pub has_declined: bool,
+    #[serde(default)]
+    pub is_flagged: bool,
+    pub reviewer_url: Option<String>,
+    /// members who voted on behalf of a group reviewer
+    #[serde(default)]
+    pub voted_for: Vec<IdentityRefWithVote>,
+}
+
+/// enum representation of Azure Pull Request Votes
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(try_from = "i8", into = "i8")]
+pub enum Vote {
+    Approve,
+    ApproveWithSuggestions,
+    #[default]
+    NoVote,
+    WaitForAuthor,
+    Reject,
+}
+
+impl From<Vote> for i8 {
+    fn from(vote: Vote) -> i8 {
+        match vote {
+            Vote::Approve => 10,
+            Vote::ApproveWithSuggestions => 5,
+            Vote::NoVote => 0,
+            Vote::WaitForAuthor => -5,
+            Vote::Reject => -10,
+        }
+    }
+}
+
+impl TryFrom<i8> for Vote {
+    type Error = String;
+
+    fn try_from(vote: i8) -> Result<Vote, String> {
+        match vote {
+            10 => Ok(Vote::Approve),
+            5 => Ok(Vote::ApproveWithSuggestions),
+            0 => Ok(Vote::NoVote),
+            -5 => Ok(Vote::WaitForAuthor),
+            -10 => Ok(Vote::Reject),
+            vote => Err(format!("unknown vote {}", vote)),
+        }
+    }
+}
+
+impl fmt::Display for Vote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Vote::Approve => "approved",
+            Vote::ApproveWithSuggestions => "approved with suggestions",
+            Vote::NoVote => "no vote",
+            Vote::WaitForAuthor => "waiting for author",
+            Vote::Reject => "rejected",
+        }
+        .fmt(f)
+    }
+}
+
+/// Body of reviewer creation and update requests
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReviewerOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) vote: Option<Vote>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) is_required: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::AzureClient;
+
+    const REVIEWERS: &str = "/org/project/_apis/git/repositories/repo/pullrequests/3/reviewers";
+
+    #[tokio::test]
+    async fn list_reviewers_and_approve() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(REVIEWERS))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 2,
+                "value": [{
+                    "id": "u1",
+                    "displayName": "Jamie Doe",
+                    "vote": -5,
+                    "isRequired": true
+                }, {
+                    "id": "g1",
+                    "displayName": "[project]\\Reviewers",
+                    "isContainer": true,
+                    "vote": 10,
+                    "votedFor": [{ "id": "u2", "vote": 10 }]
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/u2", REVIEWERS)))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_json(serde_json::json!({ "message": "Not Found" })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(format!("{}/u2", REVIEWERS)))
+            .and(body_json(serde_json::json!({ "vote": 10 })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "u2",
+                "vote": 10
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let pull = client.repo("project", "repo").pull(3);
+        let reviewers = pull.reviewers().await.unwrap();
+        assert_eq!(reviewers[0].vote, Vote::WaitForAuthor);
+        assert!(reviewers[0].is_required);
+        assert_eq!(reviewers[1].identity.is_container, Some(true));
+        assert_eq!(reviewers[1].voted_for[0].identity.id.as_deref(), Some("u2"));
+
+        let reviewer = pull.approve("u2").await.unwrap();
+        assert_eq!(reviewer.vote, Vote::Approve);
+    }
+
+    #[tokio::test]
+    async fn make_reviewer_required_keeping_vote() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/u1", REVIEWERS)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "u1",
+                "vote": 5
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(format!("{}/u1", REVIEWERS)))
+            .and(body_json(
+                serde_json::json!({ "vote": 5, "isRequired": true }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "u1",
+                "vote": 5,
+                "isRequired": true
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let reviewer = client
+            .repo("project", "repo")
+            .pull(3)
+            .set_reviewer_required("u1", true)
+            .await
+            .unwrap();
+        assert!(reviewer.is_required);
+        assert_eq!(reviewer.vote, Vote::ApproveWithSuggestions);
+    }
+
+    #[tokio::test]
+    async fn vote_keeping_required_and_add_new_reviewer() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/u1", REVIEWERS)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "u1",
+                "vote": 0,
+                "isRequired": true
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(format!("{}/u1", REVIEWERS)))
+            .and(body_json(
+                serde_json::json!({ "vote": -10, "isRequired": true }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "u1",
+                "vote": -10,
+                "isRequired": true
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/u2", REVIEWERS)))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_json(serde_json::json!({ "message": "Not Found" })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(format!("{}/u2", REVIEWERS)))
+            .and(body_json(serde_json::json!({ "isRequired": false })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "u2",
+                "vote": 0
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let pull = client.repo("project", "repo").pull(3);
+        let reviewer = pull.vote("u1", Vote::Reject).await.unwrap();
+        assert!(reviewer.is_required);
+        let reviewer = pull.add_reviewer("u2", false).await.unwrap();
+        assert_eq!(reviewer.vote, Vote::NoVote);
+    }
+
+    #[test]
+    fn unknown_vote() {
+        assert!(serde_json::from_str::<Vote>("-5").is_ok());
+        assert!(serde_json::from_str::<Vote>("3").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! Pull request statuses, as posted by external services
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/pull%20request%20statuses?view=azure-devops-rest-5.1
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::identity::IdentityRef;
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPullRequestStatus {
+    pub id: u64,
+    pub state: GitStatusState,
+    pub description: Option<String>,
+    pub context: GitStatusContext,
+    pub target_url: Option<String>,
+    /// iteration the status applies to, the whole pull request if none
+    pub iteration_id: Option<u32>,
+    pub created_by: Option<IdentityRef>,
+    pub creation_date: Option<DateTime<Utc>>,
+    pub updated_date: Option<DateTime<Utc>>,
+}
+
+/// Identifies the service and check a status comes from, e.g. genre `ci` and name `lint`
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GitStatusContext {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+}
+
+/// enum representation of Azure Git Status States
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GitStatusState {
+    NotSet,
+    Pending,
+    Succeeded,
+    Failed,
+    Error,
+    NotApplicable,
+}
+
+impl fmt::Display for GitStatusState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            GitStatusState::NotSet => "notSet",
+            GitStatusState::Pending => "pending",
+            GitStatusState::Succeeded => "succeeded",
+            GitStatusState::Failed => "failed",
+            GitStatusState::Error => "error",
+            GitStatusState::NotApplicable => "notApplicable",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPullRequestStatusOptions {
+    state: GitStatusState,
+    context: GitStatusContext,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iteration_id: Option<u32>,
+}
+
+impl GitPullRequestStatusOptions {
+    pub fn builder<N>(name: N, state: GitStatusState) -> GitPullRequestStatusOptionsBuilder
+    where
+        N: Into<String>,
+    {
+        GitPullRequestStatusOptionsBuilder(GitPullRequestStatusOptions {
+            state,
+            context: GitStatusContext {
+                name: name.into(),
+                genre: None,
+            },
+            description: None,
+            target_url: None,
+            iteration_id: None,
+        })
+    }
+}
+
+pub struct GitPullRequestStatusOptionsBuilder(GitPullRequestStatusOptions);
+
+impl GitPullRequestStatusOptionsBuilder {
+    pub fn genre<G>(&mut self, genre: G) -> &mut Self
+    where
+        G: Into<String>,
+    {
+        self.0.context.genre = Some(genre.into());
+        self
+    }
+
+    pub fn description<D>(&mut self, description: D) -> &mut Self
+    where
+        D: Into<String>,
+    {
+        self.0.description = Some(description.into());
+        self
+    }
+
+    /// link to the details of the status, e.g. a build log
+    pub fn target_url<U>(&mut self, target_url: U) -> &mut Self
+    where
+        U: Into<String>,
+    {
+        self.0.target_url = Some(target_url.into());
+        self
+    }
+
+    /// attach the status to an iteration rather than to the pull request as a whole
+    pub fn iteration_id(&mut self, iteration_id: u32) -> &mut Self {
+        self.0.iteration_id = Some(iteration_id);
+        self
+    }
+
+    pub fn build(&self) -> GitPullRequestStatusOptions {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::AzureClient;
+
+    #[tokio::test]
+    async fn post_status_for_iteration() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(
+                "/org/project/_apis/git/repositories/repo/pullrequests/3/statuses",
+            ))
+            .and(query_param("api-version", "5.1-preview.1"))
+            .and(body_json(serde_json::json!({
+                "state": "failed",
+                "context": { "name": "lint", "genre": "ci" },
+                "description": "2 findings",
+                "targetUrl": "https://ci.example.com/runs/7",
+                "iterationId": 2
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 1,
+                "state": "failed",
+                "description": "2 findings",
+                "context": { "name": "lint", "genre": "ci" },
+                "targetUrl": "https://ci.example.com/runs/7",
+                "iterationId": 2,
+                "creationDate": "2020-05-01T10:15:30.25Z"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let status = client
+            .repo("project", "repo")
+            .pull(3)
+            .create_status(
+                &GitPullRequestStatusOptions::builder("lint", GitStatusState::Failed)
+                    .genre("ci")
+                    .description("2 findings")
+                    .target_url("https://ci.example.com/runs/7")
+                    .iteration_id(2)
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(status.state, GitStatusState::Failed);
+        assert_eq!(status.context.genre.as_deref(), Some("ci"));
+    }
+}
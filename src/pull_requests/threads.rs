@@ -0,0 +1,464 @@
+//! Pull request comment threads
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/pull%20request%20threads?view=azure-devops-rest-5.1
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/pull%20request%20thread%20comments?view=azure-devops-rest-5.1
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::identity::IdentityRef;
+use crate::{AzureClient, Future};
+
+/// Comment threads of a pull request
+pub struct Threads {
+    ops: AzureClient,
+    project: String,
+    repo: String,
+    pull: u64,
+}
+
+impl Threads {
+    #[doc(hidden)]
+    pub fn new<P, R>(ops: AzureClient, project: P, repo: R, pull: u64) -> Self
+    where
+        P: Into<String>,
+        R: Into<String>,
+    {
+        Self {
+            ops,
+            project: project.into(),
+            repo: repo.into(),
+            pull,
+        }
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads?api-version=5.1
+    pub fn list(&self) -> Future<Vec<CommentThread>> {
+        self.ops.get_values(&self.path(""))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads/{threadId}?api-version=5.1
+    pub fn get(&self, thread_id: u64) -> Future<CommentThread> {
+        self.ops.get(&self.path(&format!("/{}", thread_id)))
+    }
+
+    /// Start a thread, on the pull request itself or on lines of a file
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads?api-version=5.1
+    pub fn create(&self, thread: &CommentThreadOptions) -> Future<CommentThread> {
+        self.ops.post(&self.path(""), json!(thread))
+    }
+
+    /// Resolve, close or reactivate a thread
+    ///
+    /// PATCH https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads/{threadId}?api-version=5.1
+    pub fn set_status(&self, thread_id: u64, status: CommentThreadStatus) -> Future<CommentThread> {
+        let body = serde_json::json!({ "status": status });
+        self.ops
+            .patch(&self.path(&format!("/{}", thread_id)), json!(body))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads/{threadId}/comments?api-version=5.1
+    pub fn comments(&self, thread_id: u64) -> Future<Vec<PullRequestComment>> {
+        self.ops.get_values(&self.comments_path(thread_id, ""))
+    }
+
+    /// Reply to the comment with id `parent_comment_id` of a thread
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads/{threadId}/comments?api-version=5.1
+    pub fn reply<C>(
+        &self,
+        thread_id: u64,
+        parent_comment_id: u64,
+        content: C,
+    ) -> Future<PullRequestComment>
+    where
+        C: Into<String>,
+    {
+        let comment = NewComment {
+            content: content.into(),
+            parent_comment_id: Some(parent_comment_id),
+            comment_type: Some(CommentType::Text),
+        };
+        self.ops
+            .post(&self.comments_path(thread_id, ""), json!(comment))
+    }
+
+    /// PATCH https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads/{threadId}/comments/{commentId}?api-version=5.1
+    pub fn edit_comment<C>(
+        &self,
+        thread_id: u64,
+        comment_id: u64,
+        content: C,
+    ) -> Future<PullRequestComment>
+    where
+        C: Into<String>,
+    {
+        let comment = NewComment {
+            content: content.into(),
+            parent_comment_id: None,
+            comment_type: None,
+        };
+        self.ops.patch(
+            &self.comments_path(thread_id, &format!("/{}", comment_id)),
+            json!(comment),
+        )
+    }
+
+    /// DELETE https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads/{threadId}/comments/{commentId}?api-version=5.1
+    pub fn delete_comment(
+        &self,
+        thread_id: u64,
+        comment_id: u64,
+    ) -> Future<Option<serde_json::Value>> {
+        self.ops
+            .delete(&self.comments_path(thread_id, &format!("/{}", comment_id)))
+    }
+
+    /// Users who liked a comment
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads/{threadId}/comments/{commentId}/likes?api-version=5.1-preview.1
+    pub fn likes(&self, thread_id: u64, comment_id: u64) -> Future<Vec<IdentityRef>> {
+        self.ops.get_values(&self.likes_path(thread_id, comment_id))
+    }
+
+    /// Like a comment as the authenticated user
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads/{threadId}/comments/{commentId}/likes?api-version=5.1-preview.1
+    pub fn like(&self, thread_id: u64, comment_id: u64) -> Future<Option<serde_json::Value>> {
+        self.ops
+            .post(&self.likes_path(thread_id, comment_id), Vec::new())
+    }
+
+    /// Withdraw the like of the authenticated user
+    ///
+    /// DELETE https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pullRequests/{pullRequestId}/threads/{threadId}/comments/{commentId}/likes?api-version=5.1-preview.1
+    pub fn unlike(&self, thread_id: u64, comment_id: u64) -> Future<Option<serde_json::Value>> {
+        self.ops.delete(&self.likes_path(thread_id, comment_id))
+    }
+
+    fn path(&self, more: &str) -> String {
+        format!(
+            "/{}/{}/_apis/git/repositories/{}/pullrequests/{}/threads{}",
+            self.ops.org, self.project, self.repo, self.pull, more
+        )
+    }
+
+    fn comments_path(&self, thread_id: u64, more: &str) -> String {
+        self.path(&format!("/{}/comments{}", thread_id, more))
+    }
+
+    fn likes_path(&self, thread_id: u64, comment_id: u64) -> String {
+        format!(
+            "{}?{}",
+            self.comments_path(thread_id, &format!("/{}/likes", comment_id)),
+            self.ops.api_version.preview(1)
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThread {
+    pub id: u64,
+    pub status: Option<CommentThreadStatus>,
+    /// the file and lines the thread is about, if any
+    pub thread_context: Option<ThreadContext>,
+    #[serde(default)]
+    pub comments: Vec<PullRequestComment>,
+    pub published_date: Option<DateTime<Utc>>,
+    pub last_updated_date: Option<DateTime<Utc>>,
+    pub properties: Option<serde_json::Value>,
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequestComment {
+    pub id: u64,
+    /// id of the comment replied to, `0` for the first comment of a thread
+    #[serde(default)]
+    pub parent_comment_id: u64,
+    pub author: Option<IdentityRef>,
+    pub content: Option<String>,
+    pub comment_type: Option<CommentType>,
+    pub published_date: Option<DateTime<Utc>>,
+    pub last_updated_date: Option<DateTime<Utc>>,
+    pub last_content_updated_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub users_liked: Vec<IdentityRef>,
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+/// enum representation of Azure Pull Request Thread Status
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CommentThreadStatus {
+    Unknown,
+    Active,
+    Fixed,
+    WontFix,
+    Closed,
+    ByDesign,
+    Pending,
+}
+
+impl fmt::Display for CommentThreadStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CommentThreadStatus::Unknown => "unknown",
+            CommentThreadStatus::Active => "active",
+            CommentThreadStatus::Fixed => "fixed",
+            CommentThreadStatus::WontFix => "wontFix",
+            CommentThreadStatus::Closed => "closed",
+            CommentThreadStatus::ByDesign => "byDesign",
+            CommentThreadStatus::Pending => "pending",
+        }
+        .fmt(f)
+    }
+}
+
+/// enum representation of who or what wrote a comment
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CommentType {
+    Unknown,
+    Text,
+    CodeChange,
+    System,
+}
+
+/// Location of a thread in a file, `left` being the target version and `right` the source one
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadContext {
+    /// path of the file from the repository root, e.g. `/src/lib.rs`
+    pub file_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left_file_start: Option<CommentPosition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left_file_end: Option<CommentPosition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right_file_start: Option<CommentPosition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right_file_end: Option<CommentPosition>,
+}
+
+impl ThreadContext {
+    /// lines `start` to `end` of the source version of a file, as changed by the pull request
+    pub fn right<F>(file_path: F, start: u32, end: u32) -> Self
+    where
+        F: Into<String>,
+    {
+        ThreadContext {
+            file_path: file_path.into(),
+            right_file_start: Some(CommentPosition::line(start)),
+            right_file_end: Some(CommentPosition::line(end)),
+            ..Default::default()
+        }
+    }
+
+    /// lines `start` to `end` of the target version of a file, e.g. lines removed by the pull request
+    pub fn left<F>(file_path: F, start: u32, end: u32) -> Self
+    where
+        F: Into<String>,
+    {
+        ThreadContext {
+            file_path: file_path.into(),
+            left_file_start: Some(CommentPosition::line(start)),
+            left_file_end: Some(CommentPosition::line(end)),
+            ..Default::default()
+        }
+    }
+}
+
+/// A position in a file, both line and offset starting at 1
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CommentPosition {
+    pub line: u32,
+    pub offset: u32,
+}
+
+impl CommentPosition {
+    /// the start of a line
+    pub fn line(line: u32) -> Self {
+        CommentPosition { line, offset: 1 }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NewComment {
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_comment_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment_type: Option<CommentType>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThreadOptions {
+    comments: Vec<NewComment>,
+    status: CommentThreadStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_context: Option<ThreadContext>,
+}
+
+impl CommentThreadOptions {
+    /// a new active thread starting with `content`
+    pub fn builder<C>(content: C) -> CommentThreadOptionsBuilder
+    where
+        C: Into<String>,
+    {
+        CommentThreadOptionsBuilder(CommentThreadOptions {
+            comments: vec![NewComment {
+                content: content.into(),
+                parent_comment_id: Some(0),
+                comment_type: Some(CommentType::Text),
+            }],
+            status: CommentThreadStatus::Active,
+            thread_context: None,
+        })
+    }
+}
+
+pub struct CommentThreadOptionsBuilder(CommentThreadOptions);
+
+impl CommentThreadOptionsBuilder {
+    pub fn status(&mut self, status: CommentThreadStatus) -> &mut Self {
+        self.0.status = status;
+        self
+    }
+
+    /// attach the thread to lines of a file rather than to the pull request as a whole
+    pub fn context(&mut self, context: ThreadContext) -> &mut Self {
+        self.0.thread_context = Some(context);
+        self
+    }
+
+    pub fn build(&self) -> CommentThreadOptions {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    const THREADS: &str = "/org/project/_apis/git/repositories/repo/pullrequests/3/threads";
+
+    #[tokio::test]
+    async fn create_inline_thread_and_resolve_it() {
+        let server = MockServer::start().await;
+        let thread = serde_json::json!({
+            "id": 11,
+            "status": "active",
+            "threadContext": {
+                "filePath": "/src/lib.rs",
+                "rightFileStart": { "line": 4, "offset": 1 },
+                "rightFileEnd": { "line": 6, "offset": 1 }
+            },
+            "comments": [{
+                "id": 1,
+                "parentCommentId": 0,
+                "author": { "displayName": "lint bot" },
+                "content": "unused import",
+                "commentType": "text",
+                "publishedDate": "2020-05-01T10:15:30.25Z"
+            }],
+            "publishedDate": "2020-05-01T10:15:30.25Z",
+            "isDeleted": false
+        });
+        Mock::given(method("POST"))
+            .and(path(THREADS))
+            .and(body_json(serde_json::json!({
+                "comments": [{
+                    "content": "unused import",
+                    "parentCommentId": 0,
+                    "commentType": "text"
+                }],
+                "status": "active",
+                "threadContext": {
+                    "filePath": "/src/lib.rs",
+                    "rightFileStart": { "line": 4, "offset": 1 },
+                    "rightFileEnd": { "line": 6, "offset": 1 }
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(thread.clone()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let mut fixed = thread;
+        fixed["status"] = serde_json::json!("fixed");
+        Mock::given(method("PATCH"))
+            .and(path(format!("{}/11", THREADS)))
+            .and(body_json(serde_json::json!({ "status": "fixed" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixed))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let threads = client.repo("project", "repo").pulls().pull(3).threads();
+        let thread = threads
+            .create(
+                &CommentThreadOptions::builder("unused import")
+                    .context(ThreadContext::right("/src/lib.rs", 4, 6))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(thread.comments[0].comment_type, Some(CommentType::Text));
+        assert_eq!(
+            thread.thread_context.unwrap().right_file_start,
+            Some(CommentPosition::line(4))
+        );
+
+        let thread = threads
+            .set_status(11, CommentThreadStatus::Fixed)
+            .await
+            .unwrap();
+        assert_eq!(thread.status, Some(CommentThreadStatus::Fixed));
+    }
+
+    #[tokio::test]
+    async fn reply_and_like() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/11/comments", THREADS)))
+            .and(body_json(serde_json::json!({
+                "content": "fixed",
+                "parentCommentId": 1,
+                "commentType": "text"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 2,
+                "parentCommentId": 1,
+                "content": "fixed",
+                "commentType": "text"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/11/comments/2/likes", THREADS)))
+            .and(query_param("api-version", "5.1-preview.1"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let threads = client.repo("project", "repo").pulls().pull(3).threads();
+        let reply = threads.reply(11, 1, "fixed").await.unwrap();
+        assert_eq!(reply.parent_comment_id, 1);
+        threads.like(11, reply.id).await.unwrap();
+    }
+}
@@ -0,0 +1,344 @@
+//! Git pushes: committing file changes without a local clone
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/pushes?view=azure-devops-rest-5.1
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::identity::IdentityRef;
+use crate::pull_requests::iterations::ChangeType;
+use crate::pull_requests::{GitCommitRef, GitRepositoryRef};
+use crate::refs::{branch, GitRefUpdateResult};
+use crate::{AzureClient, Future};
+
+/// Pushes of a repository
+pub struct Pushes {
+    ops: AzureClient,
+    project: String,
+    repo: String,
+}
+
+impl Pushes {
+    #[doc(hidden)]
+    pub fn new<P, R>(ops: AzureClient, project: P, repo: R) -> Self
+    where
+        P: Into<String>,
+        R: Into<String>,
+    {
+        Self {
+            ops,
+            project: project.into(),
+            repo: repo.into(),
+        }
+    }
+
+    /// Commit changes to a branch, provided it still points to the old object id of the push
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pushes?api-version=5.1
+    pub fn create(&self, push: &GitPushOptions) -> Future<GitPush> {
+        self.ops.post(&self.path(""), json!(push))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/pushes/{pushId}?api-version=5.1
+    pub fn get(&self, push_id: u64) -> Future<GitPush> {
+        self.ops.get(&self.path(&format!("/{}", push_id)))
+    }
+
+    fn path(&self, more: &str) -> String {
+        format!(
+            "/{}/{}/_apis/git/repositories/{}/pushes{}",
+            self.ops.org, self.project, self.repo, more
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPush {
+    pub push_id: u64,
+    pub date: Option<DateTime<Utc>>,
+    pub pushed_by: Option<IdentityRef>,
+    #[serde(default)]
+    pub ref_updates: Vec<GitRefUpdateResult>,
+    #[serde(default)]
+    pub commits: Vec<GitCommitRef>,
+    pub repository: Option<GitRepositoryRef>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPushOptions {
+    ref_updates: Vec<PushRefUpdate>,
+    commits: Vec<GitCommitOptions>,
+}
+
+impl GitPushOptions {
+    /// a push to `branch`, currently pointing to `old_object_id`
+    ///
+    /// To create the branch, use the commit it starts from, e.g. the tip of the branch it will
+    /// merge into, as `old_object_id`: the commits of the push are made on top of it. With
+    /// `refs::NULL_OBJECT_ID`, the first commit has no parent and existing files cannot be edited.
+    pub fn builder<O>(branch_name: &str, old_object_id: O) -> GitPushOptionsBuilder
+    where
+        O: Into<String>,
+    {
+        GitPushOptionsBuilder(GitPushOptions {
+            ref_updates: vec![PushRefUpdate {
+                name: branch(branch_name),
+                old_object_id: old_object_id.into(),
+            }],
+            commits: Vec::new(),
+        })
+    }
+}
+
+pub struct GitPushOptionsBuilder(GitPushOptions);
+
+impl GitPushOptionsBuilder {
+    pub fn commit(&mut self, commit: GitCommitOptions) -> &mut Self {
+        self.0.commits.push(commit);
+        self
+    }
+
+    pub fn build(&self) -> GitPushOptions {
+        self.0.clone()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PushRefUpdate {
+    name: String,
+    old_object_id: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitOptions {
+    comment: String,
+    changes: Vec<GitChangeOptions>,
+}
+
+impl GitCommitOptions {
+    pub fn builder<C>(comment: C) -> GitCommitOptionsBuilder
+    where
+        C: Into<String>,
+    {
+        GitCommitOptionsBuilder(GitCommitOptions {
+            comment: comment.into(),
+            changes: Vec::new(),
+        })
+    }
+}
+
+pub struct GitCommitOptionsBuilder(GitCommitOptions);
+
+impl GitCommitOptionsBuilder {
+    /// add a text file
+    pub fn add<P, C>(&mut self, path: P, content: C) -> &mut Self
+    where
+        P: Into<String>,
+        C: Into<String>,
+    {
+        self.change(ChangeType::Add, path, Some(ItemContent::raw_text(content)))
+    }
+
+    /// add a binary file
+    pub fn add_base64<P, C>(&mut self, path: P, content: C) -> &mut Self
+    where
+        P: Into<String>,
+        C: AsRef<[u8]>,
+    {
+        self.change(ChangeType::Add, path, Some(ItemContent::base64(content)))
+    }
+
+    /// replace the content of a text file
+    pub fn edit<P, C>(&mut self, path: P, content: C) -> &mut Self
+    where
+        P: Into<String>,
+        C: Into<String>,
+    {
+        self.change(ChangeType::Edit, path, Some(ItemContent::raw_text(content)))
+    }
+
+    /// replace the content of a binary file
+    pub fn edit_base64<P, C>(&mut self, path: P, content: C) -> &mut Self
+    where
+        P: Into<String>,
+        C: AsRef<[u8]>,
+    {
+        self.change(ChangeType::Edit, path, Some(ItemContent::base64(content)))
+    }
+
+    pub fn delete<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<String>,
+    {
+        self.change(ChangeType::Delete, path, None)
+    }
+
+    /// move the file at `from` to `to`
+    pub fn rename<F, T>(&mut self, from: F, to: T) -> &mut Self
+    where
+        F: Into<String>,
+        T: Into<String>,
+    {
+        self.0.changes.push(GitChangeOptions {
+            change_type: ChangeType::Rename,
+            item: ItemPath { path: to.into() },
+            source_server_item: Some(from.into()),
+            new_content: None,
+        });
+        self
+    }
+
+    pub fn build(&self) -> GitCommitOptions {
+        self.0.clone()
+    }
+
+    fn change<P>(
+        &mut self,
+        change_type: ChangeType,
+        path: P,
+        new_content: Option<ItemContent>,
+    ) -> &mut Self
+    where
+        P: Into<String>,
+    {
+        self.0.changes.push(GitChangeOptions {
+            change_type,
+            item: ItemPath { path: path.into() },
+            source_server_item: None,
+            new_content,
+        });
+        self
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GitChangeOptions {
+    change_type: ChangeType,
+    item: ItemPath,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_server_item: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_content: Option<ItemContent>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ItemPath {
+    path: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemContent {
+    content: String,
+    content_type: ItemContentType,
+}
+
+impl ItemContent {
+    fn raw_text<C>(content: C) -> Self
+    where
+        C: Into<String>,
+    {
+        ItemContent {
+            content: content.into(),
+            content_type: ItemContentType::RawText,
+        }
+    }
+
+    fn base64<C>(content: C) -> Self
+    where
+        C: AsRef<[u8]>,
+    {
+        ItemContent {
+            content: base64::encode(content),
+            content_type: ItemContentType::Base64Encoded,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ItemContentType {
+    RawText,
+    Base64Encoded,
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn push_version_bump() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/org/project/_apis/git/repositories/repo/pushes"))
+            .and(body_json(serde_json::json!({
+                "refUpdates": [{
+                    "name": "refs/heads/bump/2.0",
+                    "oldObjectId": "c4"
+                }],
+                "commits": [{
+                    "comment": "Bump version to 2.0",
+                    "changes": [{
+                        "changeType": "edit",
+                        "item": { "path": "/VERSION" },
+                        "newContent": { "content": "2.0\n", "contentType": "rawtext" }
+                    }, {
+                        "changeType": "add",
+                        "item": { "path": "/logo.png" },
+                        "newContent": { "content": "iVBO", "contentType": "base64encoded" }
+                    }, {
+                        "changeType": "rename",
+                        "item": { "path": "/CHANGES.md" },
+                        "sourceServerItem": "/HISTORY.md"
+                    }, {
+                        "changeType": "delete",
+                        "item": { "path": "/OLD_VERSION" }
+                    }]
+                }]
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "pushId": 12,
+                "date": "2020-05-01T10:15:30Z",
+                "pushedBy": { "displayName": "Release Bot" },
+                "refUpdates": [{
+                    "name": "refs/heads/bump/2.0",
+                    "oldObjectId": "c4",
+                    "newObjectId": "c5"
+                }],
+                "commits": [{ "commitId": "c5", "comment": "Bump version to 2.0" }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let push = client
+            .repo("project", "repo")
+            .pushes()
+            .create(
+                &GitPushOptions::builder("bump/2.0", "c4")
+                    .commit(
+                        GitCommitOptions::builder("Bump version to 2.0")
+                            .edit("/VERSION", "2.0\n")
+                            .add_base64("/logo.png", [0x89, 0x50, 0x4e])
+                            .rename("/HISTORY.md", "/CHANGES.md")
+                            .delete("/OLD_VERSION")
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(push.push_id, 12);
+        assert_eq!(push.ref_updates[0].new_object_id.as_deref(), Some("c5"));
+        assert_eq!(push.commits[0].commit_id, "c5");
+    }
+}
@@ -0,0 +1,144 @@
+//! Client side throttling
+//!
+//! https://docs.microsoft.com/en-us/azure/devops/integrate/concepts/rate-limits?view=azure-devops#api-client-experience
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use http::header::{HeaderMap, HeaderValue};
+
+use crate::{X_RATELIMIT_LIMIT, X_RATELIMIT_REMAINING, X_RATELIMIT_RESET};
+
+/// Resource consumption, in TSTUs, as last reported by Azure
+///
+/// Azure only reports it when a client is close to, or over, its limit, so an
+/// empty status means requests aren't being throttled.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RateLimitStatus {
+    /// TSTUs allowed within the sliding window
+    pub limit: Option<u32>,
+    /// TSTUs left before requests get throttled
+    pub remaining: Option<u32>,
+    /// when usage is expected to be back to 0
+    pub reset: Option<SystemTime>,
+}
+
+impl RateLimitStatus {
+    pub(crate) fn from_headers(headers: &HeaderMap<HeaderValue>) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .and_then(|value| value.parse::<u32>().ok())
+        };
+        RateLimitStatus {
+            limit: header(X_RATELIMIT_LIMIT),
+            remaining: header(X_RATELIMIT_REMAINING),
+            reset: header(X_RATELIMIT_RESET)
+                .map(|reset| UNIX_EPOCH + Duration::from_secs(u64::from(reset))),
+        }
+    }
+
+    /// delay before the next request when no more than `threshold` TSTUs remain
+    fn delay(&self, threshold: u32, now: SystemTime) -> Option<Duration> {
+        match (self.remaining, self.reset) {
+            (Some(remaining), Some(reset)) if remaining <= threshold => reset
+                .duration_since(now)
+                .ok()
+                .filter(|delay| *delay > Duration::from_secs(0)),
+            _ => None,
+        }
+    }
+}
+
+/// Rate limit state shared by an `AzureClient` and all of its clones
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RateLimiter {
+    status: Arc<Mutex<RateLimitStatus>>,
+    threshold: u32,
+}
+
+impl RateLimiter {
+    pub(crate) fn set_threshold(&mut self, threshold: u32) {
+        self.threshold = threshold;
+    }
+
+    pub(crate) fn status(&self) -> RateLimitStatus {
+        *self.status.lock().unwrap()
+    }
+
+    pub(crate) fn update(&self, status: RateLimitStatus) {
+        *self.status.lock().unwrap() = status;
+    }
+
+    /// delay before the next request so as not to exhaust the remaining budget
+    pub(crate) fn delay(&self) -> Option<Duration> {
+        self.status().delay(self.threshold, SystemTime::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::AzureClient;
+
+    #[test]
+    fn status_from_headers() {
+        assert_eq!(
+            RateLimitStatus::from_headers(&HeaderMap::new()),
+            RateLimitStatus::default()
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(X_RATELIMIT_LIMIT, HeaderValue::from_static("200"));
+        headers.insert(X_RATELIMIT_REMAINING, HeaderValue::from_static("15"));
+        headers.insert(X_RATELIMIT_RESET, HeaderValue::from_static("1600000000"));
+        assert_eq!(
+            RateLimitStatus::from_headers(&headers),
+            RateLimitStatus {
+                limit: Some(200),
+                remaining: Some(15),
+                reset: Some(UNIX_EPOCH + Duration::from_secs(1_600_000_000)),
+            }
+        );
+    }
+
+    #[test]
+    fn delay_below_threshold() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        let status = RateLimitStatus {
+            limit: Some(200),
+            remaining: Some(10),
+            reset: Some(now + Duration::from_secs(30)),
+        };
+        assert_eq!(status.delay(0, now), None);
+        assert_eq!(status.delay(10, now), Some(Duration::from_secs(30)));
+        assert_eq!(status.delay(10, now + Duration::from_secs(60)), None);
+        assert_eq!(RateLimitStatus::default().delay(10, now), None);
+    }
+
+    #[tokio::test]
+    async fn status_is_shared_across_clones() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(X_RATELIMIT_LIMIT, "200")
+                    .insert_header(X_RATELIMIT_REMAINING, "150")
+                    .set_body_json(serde_json::json!({})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let clone = client.clone();
+        assert_eq!(client.rate_limit_status(), RateLimitStatus::default());
+        clone.get::<Value>("/org/_apis/projects").await.unwrap();
+        let status = client.rate_limit_status();
+        assert_eq!(status.limit, Some(200));
+        assert_eq!(status.remaining, Some(150));
+    }
+}
@@ -0,0 +1,461 @@
+//! Git refs: branches and tags
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/git/refs?view=azure-devops-rest-5.1
+use std::collections::HashMap;
+
+use futures::TryFutureExt;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::errors::ErrorKind;
+use crate::identity::IdentityRef;
+use crate::{AzureClient, Future, Pagination, Stream, Values};
+
+/// object id of a ref that does not exist, used to create and delete refs
+pub const NULL_OBJECT_ID: &str = "0000000000000000000000000000000000000000";
+
+/// Refs of a repository
+pub struct Refs {
+    ops: AzureClient,
+    project: String,
+    repo: String,
+}
+
+impl Refs {
+    #[doc(hidden)]
+    pub fn new<P, R>(ops: AzureClient, project: P, repo: R) -> Self
+    where
+        P: Into<String>,
+        R: Into<String>,
+    {
+        Self {
+            ops,
+            project: project.into(),
+            repo: repo.into(),
+        }
+    }
+
+    /// List a single page of refs
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/refs?api-version=5.1
+    pub fn list(&self, options: &RefListOptions) -> Future<Vec<GitRef>> {
+        self.ops.get_values(&self.uri(options))
+    }
+
+    /// Stream every ref matching `options`, following continuation tokens until exhaustion
+    pub fn stream(&self, options: &RefListOptions) -> Stream<GitRef> {
+        self.ops
+            .get_stream(&self.uri(options), Pagination::ContinuationToken(None))
+    }
+
+    /// Create, move or delete refs
+    ///
+    /// Each update succeeds or fails on its own, and only if the ref still points to `old_object_id`,
+    /// see `GitRefUpdateResult::success`.
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/refs?api-version=5.1
+    pub fn update(&self, updates: &[GitRefUpdate]) -> Future<Vec<GitRefUpdateResult>> {
+        Box::pin(
+            self.ops
+                .post::<Values<GitRefUpdateResult>>(&self.path(""), json!(updates))
+                .map_ok(|values| values.value),
+        )
+    }
+
+    /// Create a branch pointing to `object_id`
+    ///
+    /// Fails if the server rejects the update, e.g. when the branch already exists.
+    pub fn create_branch(&self, name: &str, object_id: &str) -> Future<GitRefUpdateResult> {
+        self.update_one(GitRefUpdate::create(branch(name), object_id))
+    }
+
+    /// Delete a branch, provided it still points to `object_id`
+    pub fn delete_branch(&self, name: &str, object_id: &str) -> Future<GitRefUpdateResult> {
+        self.update_one(GitRefUpdate::delete(branch(name), object_id))
+    }
+
+    /// Create a lightweight tag pointing to `object_id`
+    pub fn create_tag(&self, name: &str, object_id: &str) -> Future<GitRefUpdateResult> {
+        self.update_one(GitRefUpdate::create(tag(name), object_id))
+    }
+
+    /// Delete a tag, provided it still points to `object_id`
+    pub fn delete_tag(&self, name: &str, object_id: &str) -> Future<GitRefUpdateResult> {
+        self.update_one(GitRefUpdate::delete(tag(name), object_id))
+    }
+
+    /// Prevent anyone but the locking user from updating a branch
+    ///
+    /// PATCH https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/refs?filter={filter}&api-version=5.1
+    pub fn lock(&self, branch: &str) -> Future<GitRef> {
+        self.set_locked(branch, true)
+    }
+
+    pub fn unlock(&self, branch: &str) -> Future<GitRef> {
+        self.set_locked(branch, false)
+    }
+
+    fn set_locked(&self, name: &str, is_locked: bool) -> Future<GitRef> {
+        let filter = branch(name);
+        let query: String = form_urlencoded::Serializer::new(String::new())
+            .append_pair("filter", filter.trim_start_matches("refs/"))
+            .finish();
+        let body = serde_json::json!({ "isLocked": is_locked });
+        self.ops
+            .patch(&self.path(&format!("?{}", query)), json!(body))
+    }
+
+    fn update_one(&self, update: GitRefUpdate) -> Future<GitRefUpdateResult> {
+        let name = update.name.clone();
+        Box::pin(self.update(&[update]).and_then(|results| async move {
+            match results.into_iter().next() {
+                Some(result) if result.success => Ok(result),
+                Some(result) => Err(ErrorKind::Msg(format!(
+                    "failed to update {}: {}",
+                    name,
+                    result.update_status.as_deref().unwrap_or("unknown status")
+                ))
+                .into()),
+                None => Err(ErrorKind::Msg(format!("no result updating {}", name)).into()),
+            }
+        }))
+    }
+
+    fn uri(&self, options: &RefListOptions) -> String {
+        match options.serialize() {
+            Some(query) => self.path(&format!("?{}", query)),
+            None => self.path(""),
+        }
+    }
+
+    fn path(&self, more: &str) -> String {
+        format!(
+            "/{}/{}/_apis/git/repositories/{}/refs{}",
+            self.ops.org, self.project, self.repo, more
+        )
+    }
+}
+
+/// full name of a branch, e.g. `refs/heads/master` for `master`
+pub fn branch(name: &str) -> String {
+    qualify(name, "refs/heads/")
+}
+
+/// full name of a tag, e.g. `refs/tags/v1.0` for `v1.0`
+pub fn tag(name: &str) -> String {
+    qualify(name, "refs/tags/")
+}
+
+fn qualify(name: &str, prefix: &str) -> String {
+    if name.starts_with("refs/") {
+        name.to_owned()
+    } else {
+        format!("{}{}", prefix, name)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRef {
+    /// full name of the ref, e.g. `refs/heads/master`
+    pub name: String,
+    pub object_id: String,
+    /// object id of the commit an annotated tag points to, see `RefListOptionsBuilder::peel_tags`
+    pub peeled_object_id: Option<String>,
+    pub creator: Option<IdentityRef>,
+    #[serde(default)]
+    pub is_locked: bool,
+    pub is_locked_by: Option<IdentityRef>,
+    pub url: Option<String>,
+}
+
+impl GitRef {
+    /// name of the branch or tag, without its `refs/heads/` or `refs/tags/` prefix
+    pub fn short_name(&self) -> &str {
+        self.name
+            .strip_prefix("refs/heads/")
+            .or_else(|| self.name.strip_prefix("refs/tags/"))
+            .unwrap_or(&self.name)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRefUpdate {
+    pub name: String,
+    pub old_object_id: String,
+    pub new_object_id: String,
+}
+
+impl GitRefUpdate {
+    /// move the ref `name` from `old_object_id` to `new_object_id`
+    pub fn new<N, O, M>(name: N, old_object_id: O, new_object_id: M) -> Self
+    where
+        N: Into<String>,
+        O: Into<String>,
+        M: Into<String>,
+    {
+        GitRefUpdate {
+            name: name.into(),
+            old_object_id: old_object_id.into(),
+            new_object_id: new_object_id.into(),
+        }
+    }
+
+    pub fn create<N, O>(name: N, object_id: O) -> Self
+    where
+        N: Into<String>,
+        O: Into<String>,
+    {
+        GitRefUpdate::new(name, NULL_OBJECT_ID, object_id)
+    }
+
+    pub fn delete<N, O>(name: N, object_id: O) -> Self
+    where
+        N: Into<String>,
+        O: Into<String>,
+    {
+        GitRefUpdate::new(name, object_id, NULL_OBJECT_ID)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRefUpdateResult {
+    pub name: String,
+    pub old_object_id: Option<String>,
+    pub new_object_id: Option<String>,
+    #[serde(default)]
+    pub success: bool,
+    /// e.g. `succeeded`, `staleOldObjectId` or `rejectedByPolicy`
+    pub update_status: Option<String>,
+    #[serde(default)]
+    pub is_locked: bool,
+    pub rejected_by: Option<String>,
+    pub custom_message: Option<String>,
+    pub repository_id: Option<String>,
+}
+
+#[derive(Default)]
+pub struct RefListOptions {
+    params: HashMap<&'static str, String>,
+}
+
+impl RefListOptions {
+    pub fn builder() -> RefListOptionsBuilder {
+        RefListOptionsBuilder::default()
+    }
+
+    /// serialize options as a string. returns None if no options are defined
+    pub fn serialize(&self) -> Option<String> {
+        if self.params.is_empty() {
+            None
+        } else {
+            let encoded: String = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&self.params)
+                .finish();
+            Some(encoded)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RefListOptionsBuilder(RefListOptions);
+
+impl RefListOptionsBuilder {
+    /// only refs starting with `filter`, e.g. `heads/release/`
+    pub fn filter<F>(&mut self, filter: F) -> &mut Self
+    where
+        F: Into<String>,
+    {
+        self.0.params.insert("filter", filter.into());
+        self
+    }
+
+    /// only refs containing `filter_contains`
+    pub fn filter_contains<F>(&mut self, filter_contains: F) -> &mut Self
+    where
+        F: Into<String>,
+    {
+        self.0
+            .params
+            .insert("filterContains", filter_contains.into());
+        self
+    }
+
+    /// also return the commit annotated tags point to
+    pub fn peel_tags(&mut self, peel_tags: bool) -> &mut Self {
+        self.0.params.insert("peelTags", peel_tags.to_string());
+        self
+    }
+
+    pub fn include_links(&mut self, include_links: bool) -> &mut Self {
+        self.0
+            .params
+            .insert("includeLinks", include_links.to_string());
+        self
+    }
+
+    /// number of refs requested per page
+    pub fn top(&mut self, top: u32) -> &mut Self {
+        self.0.params.insert("$top", top.to_string());
+        self
+    }
+
+    pub fn build(&self) -> RefListOptions {
+        RefListOptions {
+            params: self.0.params.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    const REFS: &str = "/org/project/_apis/git/repositories/repo/refs";
+
+    #[tokio::test]
+    async fn stream_release_branches() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(REFS))
+            .and(query_param("filter", "heads/release/"))
+            .and(query_param("continuationToken", "next"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 1,
+                "value": [{ "name": "refs/heads/release/2.0", "objectId": "c2" }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(REFS))
+            .and(query_param("filter", "heads/release/"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ms-continuationtoken", "next")
+                    .set_body_json(serde_json::json!({
+                        "count": 1,
+                        "value": [{
+                            "name": "refs/heads/release/1.0",
+                            "objectId": "c1",
+                            "isLocked": true,
+                            "isLockedBy": { "displayName": "Jamie Doe" }
+                        }]
+                    })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let refs: Vec<GitRef> = client
+            .repo("project", "repo")
+            .refs()
+            .stream(&RefListOptions::builder().filter("heads/release/").build())
+            .try_collect()
+            .await
+            .unwrap();
+        let names: Vec<&str> = refs.iter().map(GitRef::short_name).collect();
+        assert_eq!(names, vec!["release/1.0", "release/2.0"]);
+        assert!(refs[0].is_locked);
+    }
+
+    #[tokio::test]
+    async fn create_release_branch_and_delete_feature_branch() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(REFS))
+            .and(body_json(serde_json::json!([{
+                "name": "refs/heads/release/3.0",
+                "oldObjectId": NULL_OBJECT_ID,
+                "newObjectId": "c3"
+            }, {
+                "name": "refs/heads/feature/x",
+                "oldObjectId": "c4",
+                "newObjectId": NULL_OBJECT_ID
+            }])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 2,
+                "value": [{
+                    "name": "refs/heads/release/3.0",
+                    "newObjectId": "c3",
+                    "success": true,
+                    "updateStatus": "succeeded"
+                }, {
+                    "name": "refs/heads/feature/x",
+                    "oldObjectId": "c4",
+                    "success": false,
+                    "updateStatus": "staleOldObjectId"
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path(REFS))
+            .and(query_param("filter", "heads/release/3.0"))
+            .and(body_json(serde_json::json!({ "isLocked": true })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": "refs/heads/release/3.0",
+                "objectId": "c3",
+                "isLocked": true
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let refs = client.repo("project", "repo").refs();
+        let results = refs
+            .update(&[
+                GitRefUpdate::create(branch("release/3.0"), "c3"),
+                GitRefUpdate::delete(branch("refs/heads/feature/x"), "c4"),
+            ])
+            .await
+            .unwrap();
+        assert!(results[0].success);
+        assert_eq!(
+            results[1].update_status.as_deref(),
+            Some("staleOldObjectId")
+        );
+
+        let locked = refs.lock("release/3.0").await.unwrap();
+        assert!(locked.is_locked);
+    }
+
+    #[tokio::test]
+    async fn fail_to_delete_moved_tag() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(REFS))
+            .and(body_json(serde_json::json!([{
+                "name": "refs/tags/v1.0",
+                "oldObjectId": "c1",
+                "newObjectId": NULL_OBJECT_ID
+            }])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 1,
+                "value": [{
+                    "name": "refs/tags/v1.0",
+                    "oldObjectId": "c1",
+                    "success": false,
+                    "updateStatus": "staleOldObjectId"
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let result = client
+            .repo("project", "repo")
+            .refs()
+            .delete_tag("v1.0", "c1")
+            .await;
+        assert!(result.is_err());
+    }
+}
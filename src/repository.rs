@@ -1,10 +1,25 @@
-use crate::{AzureClient, Future};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::Value;
 
+use crate::{AzureClient, Future, Pagination, Stream};
+
+use crate::commits::Commits;
+use crate::errors::ErrorKind;
+use crate::items::Items;
 use crate::pull_requests::{PullRequest, PullRequests};
-pub use repository_create_options::RepoOptions;
+use crate::pushes::Pushes;
+use crate::refs::{branch, Refs};
+pub use repository_create_options::{
+    ParentRepositoryRef, ProjectRef, RepoOptions, RepoOptionsBuilder,
+};
 pub use repository_create_response::RepoCreateReponse;
+pub use repository_deleted::GitDeletedRepository;
+pub use repository_import_request::{
+    GitImportRequest, GitImportRequestOptions, GitImportRequestOptionsBuilder,
+    GitImportStatusDetail, ImportStatus,
+};
 pub use repository_list_options::RepoListOptions;
-pub use repository_list_response::ReposResponse;
+pub use repository_list_response::{ReposResponse, Value as ReposValue};
 pub use repository_response::RepoResponse;
 
 pub struct Repository {
@@ -47,6 +62,26 @@ impl Repository {
         )
     }
 
+    /// Commit history of the repository
+    pub fn commits(&self) -> Commits {
+        Commits::new(self.ops.clone(), self.project.as_str(), self.repo.as_str())
+    }
+
+    /// Files, folders, trees and blobs of the repository
+    pub fn items(&self) -> Items {
+        Items::new(self.ops.clone(), self.project.as_str(), self.repo.as_str())
+    }
+
+    /// Server side commits, see `Pushes::create`
+    pub fn pushes(&self) -> Pushes {
+        Pushes::new(self.ops.clone(), self.project.as_str(), self.repo.as_str())
+    }
+
+    /// Branches and tags of the repository
+    pub fn refs(&self) -> Refs {
+        Refs::new(self.ops.clone(), self.project.as_str(), self.repo.as_str())
+    }
+
     fn path(&self, more: &str) -> String {
         format!(
             "/{}/{}/_apis/git/repositories/{}{}",
@@ -58,6 +93,55 @@ impl Repository {
     pub fn get(&self) -> Future<RepoResponse> {
         self.ops.get(&self.path(""))
     }
+
+    /// PATCH https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}?api-version=5.1
+    pub fn rename(&self, name: &str) -> Future<ReposValue> {
+        self.update(serde_json::json!({ "name": name }))
+    }
+
+    /// Set the branch pull requests target by default, e.g. `main`
+    pub fn set_default_branch(&self, default_branch: &str) -> Future<ReposValue> {
+        self.update(serde_json::json!({ "defaultBranch": branch(default_branch) }))
+    }
+
+    /// Disable the repository, keeping its content but preventing any access to it
+    pub fn set_disabled(&self, is_disabled: bool) -> Future<ReposValue> {
+        self.update(serde_json::json!({ "isDisabled": is_disabled }))
+    }
+
+    fn update(&self, body: Value) -> Future<ReposValue> {
+        self.ops.patch(&self.path(""), json!(body))
+    }
+
+    /// Import the content of an external git repository into this empty repository
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/importRequests?api-version=5.1-preview.1
+    pub fn import(&self, options: &GitImportRequestOptions) -> Future<GitImportRequest> {
+        self.ops
+            .post(&self.import_requests_path(""), json!(options))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/importRequests/{importRequestId}?api-version=5.1-preview.1
+    pub fn import_request(&self, import_request_id: u64) -> Future<GitImportRequest> {
+        self.ops
+            .get(&self.import_requests_path(&format!("/{}?", import_request_id)))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/repositories/{repositoryId}/importRequests?includeAbandoned={includeAbandoned}&api-version=5.1-preview.1
+    pub fn import_requests(&self, include_abandoned: bool) -> Future<Vec<GitImportRequest>> {
+        self.ops.get_values(
+            &self.import_requests_path(&format!("?includeAbandoned={}&", include_abandoned)),
+        )
+    }
+
+    fn import_requests_path(&self, more: &str) -> String {
+        let more = if more.is_empty() { "?" } else { more };
+        self.path(&format!(
+            "/importRequests{}{}",
+            more,
+            self.ops.api_version.preview(1)
+        ))
+    }
 }
 
 mod repository_response {
@@ -164,6 +248,61 @@ impl Repositories {
         self.ops.post(&self.path(""), json!(repo))
     }
 
+    /// Fork the parent repository of `repo`, copying only `source_ref` if any, every ref otherwise
+    ///
+    /// `source_ref` is a full ref name, e.g. `refs/heads/main` or `refs/tags/v1.0`.
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/_apis/git/repositories?sourceRef={sourceRef}&api-version=5.1
+    pub fn fork(&self, repo: &RepoOptions, source_ref: Option<&str>) -> Future<RepoCreateReponse> {
+        if repo.parent_repository.is_none() {
+            return Box::pin(futures::future::err(
+                ErrorKind::Msg("a fork needs a parent repository".into()).into(),
+            ));
+        }
+        let uri = match source_ref {
+            Some(source_ref) => self.path(&format!(
+                "?sourceRef={}",
+                utf8_percent_encode(source_ref, NON_ALPHANUMERIC)
+            )),
+            None => self.path(""),
+        };
+        self.ops.post(&uri, json!(repo))
+    }
+
+    /// Soft deleted repositories, which can still be restored
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/git/recycleBin/repositories?api-version=5.1-preview.1
+    pub fn recycle_bin(&self) -> Future<Vec<GitDeletedRepository>> {
+        self.ops.get_values(&self.recycle_bin_path(""))
+    }
+
+    /// PATCH https://dev.azure.com/{organization}/{project}/_apis/git/recycleBin/repositories/{repositoryId}?api-version=5.1-preview.1
+    pub fn restore(&self, repo_id: &str) -> Future<ReposValue> {
+        let body = serde_json::json!({ "deleted": false });
+        self.ops.patch(
+            &self.recycle_bin_path(&format!("/{}", repo_id)),
+            json!(body),
+        )
+    }
+
+    /// Delete a soft deleted repository for good
+    ///
+    /// DELETE https://dev.azure.com/{organization}/{project}/_apis/git/recycleBin/repositories/{repositoryId}?api-version=5.1-preview.1
+    pub fn purge(&self, repo_id: &str) -> Future<()> {
+        self.ops
+            .delete(&self.recycle_bin_path(&format!("/{}", repo_id)))
+    }
+
+    fn recycle_bin_path(&self, more: &str) -> String {
+        format!(
+            "/{}/{}/_apis/git/recycleBin/repositories{}?{}",
+            self.ops.org,
+            self.project,
+            more,
+            self.ops.api_version.preview(1)
+        )
+    }
+
     fn path(&self, more: &str) -> String {
         format!(
             "/{}/{}/_apis/git/repositories{}",
@@ -175,19 +314,48 @@ impl Repositories {
     ///
     /// https://docs.microsoft.com/en-us/rest/api/azure/devops/git/repositories/list?view=azure-devops-rest-5.1
     pub fn list(&self, options: &RepoListOptions) -> Future<ReposResponse> {
+        self.ops.get(&self.uri(options))
+    }
+
+    /// Stream every repository of the project, following continuation tokens until exhaustion
+    pub fn stream(&self, options: &RepoListOptions) -> Stream<ReposValue> {
+        self.ops
+            .get_stream(&self.uri(options), Pagination::ContinuationToken(None))
+    }
+
+    fn uri(&self, options: &RepoListOptions) -> String {
         let mut uri = vec![self.path("")];
         if let Some(query) = options.serialize() {
             uri.push(query);
         }
-        self.ops.get(&uri.join("?"))
+        uri.join("?")
     }
 }
 
 mod repository_create_options {
     use serde::*;
-    #[derive(Debug, Default, Serialize)]
+
+    #[derive(Clone, Debug, Default, Serialize)]
+    #[serde(rename_all = "camelCase")]
     pub struct RepoOptions {
         pub name: String,
+        /// project to create the repository in, the project of the request by default
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub project: Option<ProjectRef>,
+        /// repository to fork, see `Repositories::fork`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub parent_repository: Option<ParentRepositoryRef>,
+    }
+
+    #[derive(Clone, Debug, Serialize)]
+    pub struct ProjectRef {
+        pub id: String,
+    }
+
+    #[derive(Clone, Debug, Serialize)]
+    pub struct ParentRepositoryRef {
+        pub id: String,
+        pub project: ProjectRef,
     }
 
     pub struct RepoOptionsBuilder(RepoOptions);
@@ -202,18 +370,46 @@ mod repository_create_options {
                 ..Default::default()
             })
         }
+
+        pub fn project<P>(&mut self, project_id: P) -> &mut Self
+        where
+            P: Into<String>,
+        {
+            self.0.project = Some(ProjectRef {
+                id: project_id.into(),
+            });
+            self
+        }
+
+        /// fork the repository `repo_id` of the project `project_id`
+        pub fn parent_repository<R, P>(&mut self, repo_id: R, project_id: P) -> &mut Self
+        where
+            R: Into<String>,
+            P: Into<String>,
+        {
+            self.0.parent_repository = Some(ParentRepositoryRef {
+                id: repo_id.into(),
+                project: ProjectRef {
+                    id: project_id.into(),
+                },
+            });
+            self
+        }
+
         pub fn build(&self) -> RepoOptions {
-            RepoOptions::new(self.0.name.as_str() /*,self.0.project.id.clone()*/)
+            self.0.clone()
         }
     }
 
     impl RepoOptions {
-        #[allow(clippy::too_many_arguments)] // exempted
         pub fn new<N>(name: N) -> Self
         where
             N: Into<String>,
         {
-            RepoOptions { name: name.into() }
+            RepoOptions {
+                name: name.into(),
+                ..Default::default()
+            }
         }
 
         pub fn builder<N: Into<String>>(name: N) -> RepoOptionsBuilder {
@@ -252,6 +448,146 @@ mod repository_create_response {
     }
 }
 
+mod repository_deleted {
+    use chrono::{DateTime, Utc};
+    use serde::*;
+
+    use crate::identity::IdentityRef;
+    use crate::pull_requests::TeamProjectReference;
+
+    /// A repository in the recycle bin
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GitDeletedRepository {
+        pub id: String,
+        pub name: String,
+        pub project: Option<TeamProjectReference>,
+        pub created_date: Option<DateTime<Utc>>,
+        pub deleted_date: Option<DateTime<Utc>>,
+        pub deleted_by: Option<IdentityRef>,
+    }
+}
+
+mod repository_import_request {
+    use std::fmt;
+
+    use serde::*;
+
+    use crate::pull_requests::GitRepositoryRef;
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GitImportRequest {
+        pub import_request_id: u64,
+        pub status: ImportStatus,
+        pub detailed_status: Option<GitImportStatusDetail>,
+        pub repository: Option<GitRepositoryRef>,
+        pub url: Option<String>,
+    }
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GitImportStatusDetail {
+        pub current_step: Option<u32>,
+        #[serde(default)]
+        pub all_steps: Vec<String>,
+        pub error_message: Option<String>,
+    }
+
+    /// enum representation of Azure Git Import Statuses
+    #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+    #[serde(rename_all = "camelCase")]
+    pub enum ImportStatus {
+        Queued,
+        InProgress,
+        Completed,
+        Failed,
+        Abandoned,
+    }
+
+    impl fmt::Display for ImportStatus {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match *self {
+                ImportStatus::Queued => "queued",
+                ImportStatus::InProgress => "inProgress",
+                ImportStatus::Completed => "completed",
+                ImportStatus::Failed => "failed",
+                ImportStatus::Abandoned => "abandoned",
+            }
+            .fmt(f)
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize)]
+    pub struct GitImportRequestOptions {
+        parameters: GitImportRequestParameters,
+    }
+
+    #[derive(Clone, Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct GitImportRequestParameters {
+        git_source: GitImportGitSource,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        service_endpoint_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        delete_service_endpoint_after_import_is_done: Option<bool>,
+    }
+
+    #[derive(Clone, Debug, Serialize)]
+    struct GitImportGitSource {
+        url: String,
+        overwrite: bool,
+    }
+
+    impl GitImportRequestOptions {
+        /// import the git repository at `url`
+        pub fn builder<U>(url: U) -> GitImportRequestOptionsBuilder
+        where
+            U: Into<String>,
+        {
+            GitImportRequestOptionsBuilder(GitImportRequestOptions {
+                parameters: GitImportRequestParameters {
+                    git_source: GitImportGitSource {
+                        url: url.into(),
+                        overwrite: false,
+                    },
+                    service_endpoint_id: None,
+                    delete_service_endpoint_after_import_is_done: None,
+                },
+            })
+        }
+    }
+
+    pub struct GitImportRequestOptionsBuilder(GitImportRequestOptions);
+
+    impl GitImportRequestOptionsBuilder {
+        /// service endpoint holding the credentials of a private source repository
+        pub fn service_endpoint_id<S>(&mut self, service_endpoint_id: S) -> &mut Self
+        where
+            S: Into<String>,
+        {
+            self.0.parameters.service_endpoint_id = Some(service_endpoint_id.into());
+            self
+        }
+
+        pub fn delete_service_endpoint_after_import(&mut self, delete: bool) -> &mut Self {
+            self.0
+                .parameters
+                .delete_service_endpoint_after_import_is_done = Some(delete);
+            self
+        }
+
+        pub fn overwrite(&mut self, overwrite: bool) -> &mut Self {
+            self.0.parameters.git_source.overwrite = overwrite;
+            self
+        }
+
+        pub fn build(&self) -> GitImportRequestOptions {
+            self.0.clone()
+        }
+    }
+}
+
 mod repository_list_options {
     use std::collections::HashMap;
     use url::form_urlencoded;
@@ -349,3 +685,155 @@ mod repository_list_response {
         pub description: Option<String>,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    fn repository(id: &str, name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "name": name,
+            "url": "https://dev.azure.com/org/project/_apis/git/repositories/r1",
+            "project": {
+                "id": "p1",
+                "name": "project",
+                "url": "https://dev.azure.com/org/_apis/projects/p1",
+                "state": "wellFormed",
+                "revision": 7,
+                "visibility": "private",
+                "lastUpdateTime": "2020-05-01T10:15:30Z"
+            },
+            "defaultBranch": "refs/heads/main",
+            "size": 0,
+            "remoteUrl": "https://org@dev.azure.com/org/project/_git/repo",
+            "sshUrl": "git@ssh.dev.azure.com:v3/org/project/repo",
+            "webUrl": "https://dev.azure.com/org/project/_git/repo"
+        })
+    }
+
+    #[tokio::test]
+    async fn fork_and_import() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/org/project/_apis/git/repositories"))
+            .and(query_param("sourceRef", "refs/tags/v1.0"))
+            .and(body_json(serde_json::json!({
+                "name": "fork",
+                "project": { "id": "p1" },
+                "parentRepository": { "id": "r1", "project": { "id": "p0" } }
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(repository("r2", "fork")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(
+                "/org/project/_apis/git/repositories/mirror/importRequests",
+            ))
+            .and(query_param("api-version", "5.1-preview.1"))
+            .and(body_json(serde_json::json!({
+                "parameters": {
+                    "gitSource": { "url": "https://example.com/repo.git", "overwrite": false },
+                    "serviceEndpointId": "e1",
+                    "deleteServiceEndpointAfterImportIsDone": true
+                }
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "importRequestId": 4,
+                "status": "queued",
+                "repository": { "id": "r3", "name": "mirror" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let fork = client
+            .repos("project")
+            .fork(
+                &RepoOptions::builder("fork")
+                    .project("p1")
+                    .parent_repository("r1", "p0")
+                    .build(),
+                Some("refs/tags/v1.0"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(fork.id, "r2");
+        assert!(client
+            .repos("project")
+            .fork(&RepoOptions::new("orphan"), None)
+            .await
+            .is_err());
+
+        let import = client
+            .repo("project", "mirror")
+            .import(
+                &GitImportRequestOptions::builder("https://example.com/repo.git")
+                    .service_endpoint_id("e1")
+                    .delete_service_endpoint_after_import(true)
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(import.status, ImportStatus::Queued);
+    }
+
+    #[tokio::test]
+    async fn rename_restore_and_purge_from_recycle_bin() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/org/project/_apis/git/repositories/r1"))
+            .and(body_json(serde_json::json!({ "name": "renamed" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(repository("r1", "renamed")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/project/_apis/git/recycleBin/repositories"))
+            .and(query_param("api-version", "5.1-preview.1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 1,
+                "value": [{
+                    "id": "r4",
+                    "name": "old",
+                    "deletedDate": "2020-05-01T10:15:30Z",
+                    "deletedBy": { "displayName": "Jamie Doe" }
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/org/project/_apis/git/recycleBin/repositories/r4"))
+            .and(body_json(serde_json::json!({ "deleted": false })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(repository("r4", "old")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/org/project/_apis/git/recycleBin/repositories/r5"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let renamed = client
+            .repo("project", "r1")
+            .rename("renamed")
+            .await
+            .unwrap();
+        assert_eq!(renamed.name, "renamed");
+
+        let repos = client.repos("project");
+        let deleted = repos.recycle_bin().await.unwrap();
+        assert_eq!(deleted[0].name, "old");
+        let restored = repos.restore(&deleted[0].id).await.unwrap();
+        assert_eq!(restored.id, "r4");
+        repos.purge("r5").await.unwrap();
+    }
+}
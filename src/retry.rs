@@ -0,0 +1,381 @@
+//! Retry policy
+//!
+//! https://docs.microsoft.com/en-us/azure/devops/integrate/concepts/rate-limits?view=azure-devops#api-client-experience
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use http::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use http::{Method, StatusCode};
+use hyperx::header::HttpDate;
+
+use crate::{Error, X_RATELIMIT_RESET};
+
+const X_RATELIMIT_DELAY: &str = "x-ratelimit-delay";
+
+/// Controls how failed requests are retried
+///
+/// Requests are retried when throttled (429), when the service is unavailable (502, 503, 504)
+/// or when the connection fails. The delay between attempts is whatever the service asked for
+/// through `Retry-After`, `x-ratelimit-reset` or `x-ratelimit-delay`, falling back to an
+/// exponential backoff with jitter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn builder() -> RetryPolicyBuilder {
+        RetryPolicyBuilder::default()
+    }
+
+    /// a policy that never retries
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// delay before retrying a request that failed on its `attempt`th try, or `None` if it shouldn't be retried
+    pub(crate) fn delay(
+        &self,
+        method: &Method,
+        attempt: u32,
+        failure: &Failure,
+    ) -> Option<Duration> {
+        if !failure.transient
+            || attempt >= self.max_attempts
+            || !(self.retry_non_idempotent || method.is_idempotent())
+        {
+            return None;
+        }
+        Some(failure.retry_after.unwrap_or_else(|| self.backoff(attempt)))
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(1 << exponent)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        if self.jitter {
+            // anywhere between half and the whole backoff
+            backoff / 2 + backoff.mul_f64(fastrand::f64() / 2.0)
+        } else {
+            backoff
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RetryPolicyBuilder(RetryPolicy);
+
+impl RetryPolicyBuilder {
+    /// maximum number of attempts, including the first one
+    pub fn max_attempts(&mut self, max_attempts: u32) -> &mut Self {
+        self.0.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// backoff before the first retry, doubled on each subsequent one
+    pub fn initial_backoff(&mut self, initial_backoff: Duration) -> &mut Self {
+        self.0.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// upper bound of the exponential backoff
+    pub fn max_backoff(&mut self, max_backoff: Duration) -> &mut Self {
+        self.0.max_backoff = max_backoff;
+        self
+    }
+
+    /// randomize backoffs so that concurrent clients don't retry in lockstep
+    pub fn jitter(&mut self, jitter: bool) -> &mut Self {
+        self.0.jitter = jitter;
+        self
+    }
+
+    /// also retry POST and PATCH requests, which may not be safe to repeat
+    pub fn retry_non_idempotent(&mut self, retry_non_idempotent: bool) -> &mut Self {
+        self.0.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    pub fn build(&self) -> RetryPolicy {
+        self.0.clone()
+    }
+}
+
+/// A failed request attempt
+pub(crate) struct Failure {
+    pub(crate) error: Error,
+    /// whether trying again may succeed
+    pub(crate) transient: bool,
+    /// delay requested by the service before trying again
+    pub(crate) retry_after: Option<Duration>,
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        Failure {
+            error,
+            transient: false,
+            retry_after: None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Failure {
+    fn from(error: reqwest::Error) -> Self {
+        Failure {
+            transient: error.is_connect() || error.is_timeout(),
+            error: error.into(),
+            retry_after: None,
+        }
+    }
+}
+
+/// whether a request that failed with `status` may succeed if tried again
+pub(crate) fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// duration until the unix timestamp `reset`
+pub(crate) fn until(reset: u64) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    Duration::from_secs(reset.saturating_sub(now))
+}
+
+/// longest delay requested by the `Retry-After`, `x-ratelimit-reset` and `x-ratelimit-delay` headers
+pub(crate) fn retry_after(headers: &HeaderMap<HeaderValue>) -> Option<Duration> {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+    };
+    let retry_after = header(RETRY_AFTER.as_str()).and_then(|value| {
+        value
+            .parse::<u64>()
+            .map(Duration::from_secs)
+            .ok()
+            .or_else(|| {
+                let date: SystemTime = value.parse::<HttpDate>().ok()?.into();
+                Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+            })
+    });
+    let reset = header(X_RATELIMIT_RESET)
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(until);
+    let delay = header(X_RATELIMIT_DELAY)
+        .and_then(|value| value.parse::<f64>().ok())
+        .filter(|delay| delay.is_finite() && *delay >= 0.0)
+        .map(Duration::from_secs_f64);
+    retry_after.into_iter().chain(reset).chain(delay).max()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+    use serde_json::Value;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    use super::*;
+    use crate::{AzureClient, ErrorKind};
+
+    /// answers with each of `responses` in turn, then keeps repeating the last one
+    struct Sequence {
+        responses: Vec<ResponseTemplate>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Respond for Sequence {
+        fn respond(&self, _: &Request) -> ResponseTemplate {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            self.responses[call.min(self.responses.len() - 1)].clone()
+        }
+    }
+
+    async fn serve(verb: &str, responses: Vec<ResponseTemplate>) -> (MockServer, Arc<AtomicUsize>) {
+        let server = MockServer::start().await;
+        let calls = Arc::new(AtomicUsize::new(0));
+        Mock::given(method(verb))
+            .and(path("/org/_apis/wit/workItems/1"))
+            .respond_with(Sequence {
+                responses,
+                calls: calls.clone(),
+            })
+            .mount(&server)
+            .await;
+        (server, calls)
+    }
+
+    fn client_for(server: &MockServer, policy: RetryPolicy) -> AzureClient {
+        let mut client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        client.set_retry_policy(policy);
+        client
+    }
+
+    fn fast() -> RetryPolicyBuilder {
+        let mut builder = RetryPolicy::builder();
+        builder
+            .initial_backoff(Duration::from_millis(1))
+            .max_backoff(Duration::from_millis(5));
+        builder
+    }
+
+    fn throttled() -> ResponseTemplate {
+        ResponseTemplate::new(429)
+            .insert_header("Retry-After", "0")
+            .set_body_json(serde_json::json!({ "message": "throttled" }))
+    }
+
+    fn ok() -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": 1 }))
+    }
+
+    #[test]
+    fn backoff_is_exponential_and_bounded() {
+        let policy = RetryPolicy::builder()
+            .initial_backoff(Duration::from_secs(1))
+            .max_backoff(Duration::from_secs(5))
+            .jitter(false)
+            .build();
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4), Duration::from_secs(5));
+        assert_eq!(policy.backoff(100), Duration::from_secs(5));
+
+        let jittered = RetryPolicy::builder()
+            .initial_backoff(Duration::from_secs(4))
+            .build();
+        let backoff = jittered.backoff(1);
+        assert!(backoff >= Duration::from_secs(2) && backoff <= Duration::from_secs(4));
+    }
+
+    #[test]
+    fn retry_after_headers() {
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        headers.insert(X_RATELIMIT_DELAY, HeaderValue::from_static("7.5"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(7500)));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(0)));
+    }
+
+    #[test]
+    fn only_idempotent_methods_by_default() {
+        let failure = Failure {
+            error: ErrorKind::Msg("throttled".into()).into(),
+            transient: true,
+            retry_after: Some(Duration::from_secs(1)),
+        };
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.delay(&Method::GET, 1, &failure),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(policy.delay(&Method::POST, 1, &failure), None);
+        assert_eq!(policy.delay(&Method::GET, 4, &failure), None);
+
+        let policy = RetryPolicy::builder().retry_non_idempotent(true).build();
+        assert_eq!(
+            policy.delay(&Method::PATCH, 1, &failure),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_throttled_requests() {
+        let (server, calls) = serve("GET", vec![throttled(), throttled(), ok()]).await;
+        let client = client_for(&server, fast().build());
+        let value: Value = client.get("/org/_apis/wit/workItems/1").await.unwrap();
+        assert_eq!(value["id"], 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let (server, calls) = serve("GET", vec![throttled()]).await;
+        let client = client_for(&server, fast().max_attempts(2).build());
+        let error = client
+            .get::<Value>("/org/_apis/wit/workItems/1")
+            .await
+            .unwrap_err();
+        match error.kind() {
+            ErrorKind::Fault { code, .. } => assert_eq!(*code, StatusCode::TOO_MANY_REQUESTS),
+            kind => panic!("unexpected error {:?}", kind),
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn post_is_opt_in() {
+        let (server, calls) = serve("POST", vec![throttled(), ok()]).await;
+        let client = client_for(&server, fast().build());
+        let result = client
+            .post::<Value>("/org/_apis/wit/workItems/1", b"{}".to_vec())
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let (server, calls) = serve("POST", vec![throttled(), ok()]).await;
+        let client = client_for(&server, fast().retry_non_idempotent(true).build());
+        let value: Value = client
+            .post("/org/_apis/wit/workItems/1", b"{}".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(value["id"], 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_throttled_downloads() {
+        let content = ResponseTemplate::new(200).set_body_bytes(b"trigger:\n- master\n".to_vec());
+        let (server, calls) = serve("GET", vec![throttled(), content]).await;
+        let client = client_for(&server, fast().build());
+        let chunks: Vec<Bytes> = client
+            .download("/org/_apis/wit/workItems/1")
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks.concat(), b"trigger:\n- master\n".to_vec());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
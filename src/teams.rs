@@ -0,0 +1,673 @@
+//! Teams, their settings and capacity
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/core/teams?view=azure-devops-rest-5.1
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/work/teamsettings?view=azure-devops-rest-5.1
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::identity::IdentityRef;
+use crate::{AzureClient, Future, Pagination, Stream};
+
+/// Teams of a project
+pub struct Teams {
+    ops: AzureClient,
+    project: String,
+}
+
+impl Teams {
+    #[doc(hidden)]
+    pub fn new<P>(ops: AzureClient, project: P) -> Self
+    where
+        P: Into<String>,
+    {
+        Teams {
+            ops,
+            project: project.into(),
+        }
+    }
+
+    /// List a single page of teams, 100 by default
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/projects/{projectId}/teams?api-version=5.1
+    pub fn list(&self, options: &TeamListOptions) -> Future<Vec<WebApiTeam>> {
+        self.ops.get_values(&self.uri(options))
+    }
+
+    /// Stream every team, requesting pages until exhaustion
+    pub fn stream(&self, options: &TeamListOptions) -> Stream<WebApiTeam> {
+        self.ops
+            .get_stream(&self.uri(options), Pagination::top_skip(None, None))
+    }
+
+    /// POST https://dev.azure.com/{organization}/_apis/projects/{projectId}/teams?api-version=5.1
+    pub fn create(&self, team: &TeamOptions) -> Future<WebApiTeam> {
+        self.ops.post(&self.path(), json!(team))
+    }
+
+    fn uri(&self, options: &TeamListOptions) -> String {
+        match options.serialize() {
+            Some(query) => format!("{}?{}", self.path(), query),
+            None => self.path(),
+        }
+    }
+
+    fn path(&self) -> String {
+        format!("/{}/_apis/projects/{}/teams", self.ops.org, self.project)
+    }
+}
+
+/// A team of a project, along with its settings
+pub struct Team {
+    ops: AzureClient,
+    project: String,
+    team: String,
+}
+
+impl Team {
+    #[doc(hidden)]
+    pub fn new<P, T>(ops: AzureClient, project: P, team: T) -> Self
+    where
+        P: Into<String>,
+        T: Into<String>,
+    {
+        Team {
+            ops,
+            project: project.into(),
+            team: team.into(),
+        }
+    }
+
+    /// GET https://dev.azure.com/{organization}/_apis/projects/{projectId}/teams/{teamId}?api-version=5.1
+    pub fn get(&self) -> Future<WebApiTeam> {
+        self.ops.get(&self.path(""))
+    }
+
+    /// Rename a team or change its description
+    ///
+    /// PATCH https://dev.azure.com/{organization}/_apis/projects/{projectId}/teams/{teamId}?api-version=5.1
+    pub fn update(&self, team: &TeamOptions) -> Future<WebApiTeam> {
+        self.ops.patch(&self.path(""), json!(team))
+    }
+
+    /// DELETE https://dev.azure.com/{organization}/_apis/projects/{projectId}/teams/{teamId}?api-version=5.1
+    pub fn delete(&self) -> Future<Option<serde_json::Value>> {
+        self.ops.delete(&self.path(""))
+    }
+
+    /// Stream the members of the team
+    ///
+    /// GET https://dev.azure.com/{organization}/_apis/projects/{projectId}/teams/{teamId}/members?api-version=5.1
+    pub fn members(&self) -> Stream<TeamMember> {
+        self.ops
+            .get_stream(&self.path("/members"), Pagination::top_skip(None, None))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings?api-version=5.1
+    pub fn settings(&self) -> Future<TeamSetting> {
+        self.ops.get(&self.work_path(""))
+    }
+
+    /// PATCH https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings?api-version=5.1
+    pub fn update_settings(&self, settings: &TeamSettingsPatch) -> Future<TeamSetting> {
+        self.ops.patch(&self.work_path(""), json!(settings))
+    }
+
+    /// Iterations the team is subscribed to, optionally only the current one
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings/iterations?$timeframe={timeframe}&api-version=5.1
+    pub fn iterations(&self, current_only: bool) -> Future<Vec<TeamSettingsIteration>> {
+        let query = if current_only {
+            "?$timeframe=current"
+        } else {
+            ""
+        };
+        self.ops
+            .get_values(&self.work_path(&format!("/iterations{}", query)))
+    }
+
+    /// Subscribe the team to an iteration, given the identifier of its classification node
+    ///
+    /// POST https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings/iterations?api-version=5.1
+    pub fn add_iteration(&self, iteration_id: &str) -> Future<TeamSettingsIteration> {
+        let body = serde_json::json!({ "id": iteration_id });
+        self.ops.post(&self.work_path("/iterations"), json!(body))
+    }
+
+    /// DELETE https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings/iterations/{id}?api-version=5.1
+    pub fn remove_iteration(&self, iteration_id: &str) -> Future<Option<serde_json::Value>> {
+        self.ops
+            .delete(&self.work_path(&format!("/iterations/{}", iteration_id)))
+    }
+
+    /// Area paths owned by the team
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings/teamfieldvalues?api-version=5.1
+    pub fn field_values(&self) -> Future<TeamFieldValues> {
+        self.ops.get(&self.work_path("/teamfieldvalues"))
+    }
+
+    /// PATCH https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings/teamfieldvalues?api-version=5.1
+    pub fn update_field_values(&self, values: &TeamFieldValuesPatch) -> Future<TeamFieldValues> {
+        self.ops
+            .patch(&self.work_path("/teamfieldvalues"), json!(values))
+    }
+
+    /// Capacity of each team member during an iteration
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings/iterations/{iterationId}/capacities?api-version=5.1
+    pub fn capacities(&self, iteration_id: &str) -> Future<Vec<TeamMemberCapacity>> {
+        self.ops
+            .get_values(&self.work_path(&format!("/iterations/{}/capacities", iteration_id)))
+    }
+
+    /// Replace the activities and days off of a team member during an iteration
+    ///
+    /// PATCH https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings/iterations/{iterationId}/capacities/{teamMemberId}?api-version=5.1
+    pub fn update_capacity(
+        &self,
+        iteration_id: &str,
+        team_member_id: &str,
+        capacity: &CapacityPatch,
+    ) -> Future<TeamMemberCapacity> {
+        self.ops.patch(
+            &self.work_path(&format!(
+                "/iterations/{}/capacities/{}",
+                iteration_id, team_member_id
+            )),
+            json!(capacity),
+        )
+    }
+
+    /// Days off of the whole team during an iteration
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings/iterations/{iterationId}/teamdaysoff?api-version=5.1
+    pub fn days_off(&self, iteration_id: &str) -> Future<TeamDaysOff> {
+        self.ops
+            .get(&self.work_path(&format!("/iterations/{}/teamdaysoff", iteration_id)))
+    }
+
+    /// PATCH https://dev.azure.com/{organization}/{project}/{team}/_apis/work/teamsettings/iterations/{iterationId}/teamdaysoff?api-version=5.1
+    pub fn set_days_off(&self, iteration_id: &str, days_off: &[DateRange]) -> Future<TeamDaysOff> {
+        let body = serde_json::json!({ "daysOff": days_off });
+        self.ops.patch(
+            &self.work_path(&format!("/iterations/{}/teamdaysoff", iteration_id)),
+            json!(body),
+        )
+    }
+
+    fn path(&self, more: &str) -> String {
+        format!(
+            "/{}/_apis/projects/{}/teams/{}{}",
+            self.ops.org, self.project, self.team, more
+        )
+    }
+
+    fn work_path(&self, more: &str) -> String {
+        format!(
+            "/{}/{}/{}/_apis/work/teamsettings{}",
+            self.ops.org, self.project, self.team, more
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebApiTeam {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub identity_url: Option<String>,
+    pub project_id: Option<String>,
+    pub project_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMember {
+    pub identity: IdentityRef,
+    #[serde(default)]
+    pub is_team_admin: bool,
+}
+
+/// Name and description of a team to create or update
+#[derive(Debug, Default, Serialize)]
+pub struct TeamOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl TeamOptions {
+    pub fn builder() -> TeamOptionsBuilder {
+        TeamOptionsBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct TeamOptionsBuilder(TeamOptions);
+
+impl TeamOptionsBuilder {
+    pub fn name<N>(&mut self, name: N) -> &mut Self
+    where
+        N: Into<String>,
+    {
+        self.0.name = Some(name.into());
+        self
+    }
+
+    pub fn description<D>(&mut self, description: D) -> &mut Self
+    where
+        D: Into<String>,
+    {
+        self.0.description = Some(description.into());
+        self
+    }
+
+    pub fn build(&self) -> TeamOptions {
+        TeamOptions {
+            name: self.0.name.clone(),
+            description: self.0.description.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TeamListOptions {
+    params: HashMap<&'static str, String>,
+}
+
+impl TeamListOptions {
+    pub fn builder() -> TeamListOptionsBuilder {
+        TeamListOptionsBuilder::default()
+    }
+
+    /// serialize options as a string. returns None if no options are defined
+    pub fn serialize(&self) -> Option<String> {
+        if self.params.is_empty() {
+            None
+        } else {
+            let encoded: String = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&self.params)
+                .finish();
+            Some(encoded)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TeamListOptionsBuilder(TeamListOptions);
+
+impl TeamListOptionsBuilder {
+    /// only list the teams the authenticated user is a member of
+    pub fn mine(&mut self, mine: bool) -> &mut Self {
+        self.0.params.insert("$mine", mine.to_string());
+        self
+    }
+
+    pub fn expand_identity(&mut self, expand_identity: bool) -> &mut Self {
+        self.0
+            .params
+            .insert("$expandIdentity", expand_identity.to_string());
+        self
+    }
+
+    pub fn build(&self) -> TeamListOptions {
+        TeamListOptions {
+            params: self.0.params.clone(),
+        }
+    }
+}
+
+/// Reference to an iteration, as found in team settings
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TeamSettingsIterationRef {
+    pub id: String,
+    pub name: Option<String>,
+    pub path: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSetting {
+    pub backlog_iteration: Option<TeamSettingsIterationRef>,
+    /// visibility of each backlog level, keyed by category reference name
+    #[serde(default)]
+    pub backlog_visibilities: HashMap<String, bool>,
+    pub bugs_behavior: Option<BugsBehavior>,
+    pub default_iteration: Option<TeamSettingsIterationRef>,
+    pub default_iteration_macro: Option<String>,
+    #[serde(default)]
+    pub working_days: Vec<DayOfWeek>,
+    pub url: Option<String>,
+}
+
+/// enum representation of how bugs show up on backlogs
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BugsBehavior {
+    Off,
+    AsRequirements,
+    AsTasks,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DayOfWeek {
+    Sunday,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+}
+
+impl fmt::Display for DayOfWeek {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DayOfWeek::Sunday => "sunday",
+            DayOfWeek::Monday => "monday",
+            DayOfWeek::Tuesday => "tuesday",
+            DayOfWeek::Wednesday => "wednesday",
+            DayOfWeek::Thursday => "thursday",
+            DayOfWeek::Friday => "friday",
+            DayOfWeek::Saturday => "saturday",
+        }
+        .fmt(f)
+    }
+}
+
+/// Team settings to change, the others being left as they are
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSettingsPatch {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backlog_iteration: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backlog_visibilities: Option<HashMap<String, bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bugs_behavior: Option<BugsBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_iteration: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_iteration_macro: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    working_days: Option<Vec<DayOfWeek>>,
+}
+
+impl TeamSettingsPatch {
+    pub fn builder() -> TeamSettingsPatchBuilder {
+        TeamSettingsPatchBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct TeamSettingsPatchBuilder(TeamSettingsPatch);
+
+impl TeamSettingsPatchBuilder {
+    /// identifier of the iteration whose children make up the team's sprints
+    pub fn backlog_iteration<I>(&mut self, iteration_id: I) -> &mut Self
+    where
+        I: Into<String>,
+    {
+        self.0.backlog_iteration = Some(iteration_id.into());
+        self
+    }
+
+    /// show or hide a backlog level, e.g. `Microsoft.EpicCategory`
+    pub fn backlog_visibility<C>(&mut self, category: C, visible: bool) -> &mut Self
+    where
+        C: Into<String>,
+    {
+        self.0
+            .backlog_visibilities
+            .get_or_insert_with(HashMap::new)
+            .insert(category.into(), visible);
+        self
+    }
+
+    pub fn bugs_behavior(&mut self, bugs_behavior: BugsBehavior) -> &mut Self {
+        self.0.bugs_behavior = Some(bugs_behavior);
+        self
+    }
+
+    /// identifier of the iteration new work items are assigned to
+    pub fn default_iteration<I>(&mut self, iteration_id: I) -> &mut Self
+    where
+        I: Into<String>,
+    {
+        self.0.default_iteration = Some(iteration_id.into());
+        self
+    }
+
+    /// assign new work items to a moving iteration, e.g. `@currentIteration`
+    pub fn default_iteration_macro<M>(&mut self, default_iteration_macro: M) -> &mut Self
+    where
+        M: Into<String>,
+    {
+        self.0.default_iteration_macro = Some(default_iteration_macro.into());
+        self
+    }
+
+    pub fn working_days<I>(&mut self, working_days: I) -> &mut Self
+    where
+        I: IntoIterator<Item = DayOfWeek>,
+    {
+        self.0.working_days = Some(working_days.into_iter().collect());
+        self
+    }
+
+    pub fn build(&self) -> TeamSettingsPatch {
+        self.0.clone()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSettingsIteration {
+    pub id: String,
+    pub name: String,
+    pub path: Option<String>,
+    pub attributes: Option<TeamIterationAttributes>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamIterationAttributes {
+    pub start_date: Option<DateTime<Utc>>,
+    pub finish_date: Option<DateTime<Utc>>,
+    /// `past`, `current` or `future`
+    pub time_frame: Option<String>,
+}
+
+/// Values of the field, usually `System.AreaPath`, that decides which work items belong to a team
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamFieldValues {
+    pub default_value: Option<String>,
+    pub field: Option<TeamField>,
+    #[serde(default)]
+    pub values: Vec<TeamFieldValue>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamField {
+    pub reference_name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamFieldValue {
+    pub value: String,
+    pub include_children: bool,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamFieldValuesPatch {
+    pub default_value: String,
+    pub values: Vec<TeamFieldValue>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMemberCapacity {
+    pub team_member: IdentityRef,
+    #[serde(default)]
+    pub activities: Vec<Activity>,
+    #[serde(default)]
+    pub days_off: Vec<DateRange>,
+    pub url: Option<String>,
+}
+
+/// Hours per day a team member can spend on an activity, e.g. `Development`
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Activity {
+    pub capacity_per_day: f64,
+    pub name: String,
+}
+
+/// An inclusive range of days
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DateRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityPatch {
+    pub activities: Vec<Activity>,
+    pub days_off: Vec<DateRange>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamDaysOff {
+    #[serde(default)]
+    pub days_off: Vec<DateRange>,
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use futures::TryStreamExt;
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn stream_team_members() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/projects/project/teams/Platform/members"))
+            .and(query_param("$skip", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 1,
+                "value": [{
+                    "identity": { "displayName": "Jamie Doe", "id": "b9c2d3a1" },
+                    "isTeamAdmin": true
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let members: Vec<TeamMember> = client
+            .project("project")
+            .team("Platform")
+            .members()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(members.len(), 1);
+        assert!(members[0].is_team_admin);
+    }
+
+    #[tokio::test]
+    async fn update_settings_and_capacity() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/org/project/Platform/_apis/work/teamsettings"))
+            .and(body_json(serde_json::json!({
+                "bugsBehavior": "asTasks",
+                "workingDays": ["monday", "tuesday"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "bugsBehavior": "asTasks",
+                "workingDays": ["monday", "tuesday"],
+                "backlogVisibilities": { "Microsoft.EpicCategory": false },
+                "defaultIterationMacro": "@currentIteration"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path(
+                "/org/project/Platform/_apis/work/teamsettings/iterations/i1/capacities/m1",
+            ))
+            .and(body_json(serde_json::json!({
+                "activities": [{ "capacityPerDay": 6.0, "name": "Development" }],
+                "daysOff": [{ "start": "2020-05-04T00:00:00Z", "end": "2020-05-05T00:00:00Z" }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "teamMember": { "id": "m1" },
+                "activities": [{ "capacityPerDay": 6, "name": "Development" }],
+                "daysOff": [{ "start": "2020-05-04T00:00:00Z", "end": "2020-05-05T00:00:00Z" }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let team = client.project("project").team("Platform");
+        let settings = team
+            .update_settings(
+                &TeamSettingsPatch::builder()
+                    .bugs_behavior(BugsBehavior::AsTasks)
+                    .working_days(vec![DayOfWeek::Monday, DayOfWeek::Tuesday])
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(settings.working_days.len(), 2);
+        assert_eq!(
+            settings.backlog_visibilities.get("Microsoft.EpicCategory"),
+            Some(&false)
+        );
+
+        let capacity = team
+            .update_capacity(
+                "i1",
+                "m1",
+                &CapacityPatch {
+                    activities: vec![Activity {
+                        capacity_per_day: 6.0,
+                        name: "Development".into(),
+                    }],
+                    days_off: vec![DateRange {
+                        start: Utc.with_ymd_and_hms(2020, 5, 4, 0, 0, 0).unwrap(),
+                        end: Utc.with_ymd_and_hms(2020, 5, 5, 0, 0, 0).unwrap(),
+                    }],
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(capacity.activities[0].capacity_per_day, 6.0);
+    }
+}
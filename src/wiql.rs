@@ -0,0 +1,806 @@
+//! Work Item Query Language
+//!
+//! https://docs.microsoft.com/en-us/azure/devops/boards/queries/wiql-syntax
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::work_items::WorkItemRef;
+use crate::SortDirection;
+
+/// A WIQL query, serialized as the body of a query request
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Wiql {
+    pub query: String,
+    /// whether dates are rendered with their time, see `WiqlBuilder::time_precision`
+    #[serde(skip)]
+    pub(crate) time_precision: bool,
+}
+
+impl Wiql {
+    /// a query written by hand
+    pub fn new<Q>(query: Q) -> Self
+    where
+        Q: Into<String>,
+    {
+        Wiql {
+            query: query.into(),
+            time_precision: false,
+        }
+    }
+
+    pub fn builder() -> WiqlBuilder {
+        WiqlBuilder::default()
+    }
+}
+
+/// enum representation of the sources a query can select from
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WiqlSource {
+    #[default]
+    WorkItems,
+    WorkItemLinks,
+}
+
+impl fmt::Display for WiqlSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            WiqlSource::WorkItems => "WorkItems",
+            WiqlSource::WorkItemLinks => "WorkItemLinks",
+        }
+        .fmt(f)
+    }
+}
+
+/// enum representation of link query modes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LinkMode {
+    /// return only source items with a matching link
+    MustContain,
+    /// return source items whether or not they have a matching link
+    MayContain,
+    /// return only source items without a matching link
+    DoesNotContain,
+    /// return a tree of links of the given type
+    Recursive,
+}
+
+impl fmt::Display for LinkMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            LinkMode::MustContain => "MustContain",
+            LinkMode::MayContain => "MayContain",
+            LinkMode::DoesNotContain => "DoesNotContain",
+            LinkMode::Recursive => "Recursive",
+        }
+        .fmt(f)
+    }
+}
+
+/// enum representation of WIQL comparison operators
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Contains,
+    NotContains,
+    ContainsWords,
+    NotContainsWords,
+    In,
+    NotIn,
+    InGroup,
+    NotInGroup,
+    Under,
+    NotUnder,
+    Ever,
+    NotEver,
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Operator::Equal => "=",
+            Operator::NotEqual => "<>",
+            Operator::Greater => ">",
+            Operator::GreaterOrEqual => ">=",
+            Operator::Less => "<",
+            Operator::LessOrEqual => "<=",
+            Operator::Contains => "CONTAINS",
+            Operator::NotContains => "NOT CONTAINS",
+            Operator::ContainsWords => "CONTAINS WORDS",
+            Operator::NotContainsWords => "NOT CONTAINS WORDS",
+            Operator::In => "IN",
+            Operator::NotIn => "NOT IN",
+            Operator::InGroup => "IN GROUP",
+            Operator::NotInGroup => "NOT IN GROUP",
+            Operator::Under => "UNDER",
+            Operator::NotUnder => "NOT UNDER",
+            Operator::Ever => "EVER",
+            Operator::NotEver => "NOT EVER",
+        }
+        .fmt(f)
+    }
+}
+
+/// enum representation of WIQL macros
+#[derive(Clone, Debug, PartialEq)]
+pub enum Macro {
+    /// the user running the query
+    Me,
+    /// the project the query runs in
+    Project,
+    /// today, shifted by the given number of days
+    Today(i32),
+    /// the current iteration of the team the query runs for
+    CurrentIteration,
+    /// any other macro, written without its leading `@`
+    Other(String),
+}
+
+impl fmt::Display for Macro {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Macro::Me => write!(f, "@Me"),
+            Macro::Project => write!(f, "@project"),
+            Macro::Today(0) => write!(f, "@Today"),
+            Macro::Today(days) if *days < 0 => write!(f, "@Today - {}", -days),
+            Macro::Today(days) => write!(f, "@Today + {}", days),
+            Macro::CurrentIteration => write!(f, "@CurrentIteration"),
+            Macro::Other(name) => write!(f, "@{}", name),
+        }
+    }
+}
+
+/// The right hand side of a condition
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    /// a string literal, quoted and escaped when rendered
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// a date, rendered without its time unless the query has time precision
+    Date(DateTime<Utc>),
+    Macro(Macro),
+    /// another field, compared to with `[Field]`
+    Field(String),
+    /// a list, for `IN` and `NOT IN`
+    List(Vec<Operand>),
+}
+
+impl Operand {
+    /// render the operand, dates with their time if `time_precision`
+    fn render(&self, time_precision: bool) -> String {
+        match self {
+            Operand::Str(value) => format!("'{}'", value.replace('\'', "''")),
+            Operand::Int(value) => value.to_string(),
+            Operand::Float(value) => value.to_string(),
+            Operand::Bool(value) => value.to_string(),
+            Operand::Date(value) if time_precision => {
+                format!("'{}'", value.to_rfc3339_opts(SecondsFormat::Secs, true))
+            }
+            Operand::Date(value) => format!("'{}'", value.format("%Y-%m-%d")),
+            Operand::Macro(value) => value.to_string(),
+            Operand::Field(field) => field_ref(field),
+            Operand::List(values) => {
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|value| value.render(time_precision))
+                    .collect();
+                format!("({})", values.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(false).fmt(f)
+    }
+}
+
+impl From<&str> for Operand {
+    fn from(value: &str) -> Self {
+        Operand::Str(value.to_owned())
+    }
+}
+
+impl From<String> for Operand {
+    fn from(value: String) -> Self {
+        Operand::Str(value)
+    }
+}
+
+impl From<i64> for Operand {
+    fn from(value: i64) -> Self {
+        Operand::Int(value)
+    }
+}
+
+impl From<i32> for Operand {
+    fn from(value: i32) -> Self {
+        Operand::Int(i64::from(value))
+    }
+}
+
+impl From<f64> for Operand {
+    fn from(value: f64) -> Self {
+        Operand::Float(value)
+    }
+}
+
+impl From<bool> for Operand {
+    fn from(value: bool) -> Self {
+        Operand::Bool(value)
+    }
+}
+
+impl From<DateTime<Utc>> for Operand {
+    fn from(value: DateTime<Utc>) -> Self {
+        Operand::Date(value)
+    }
+}
+
+impl From<Macro> for Operand {
+    fn from(value: Macro) -> Self {
+        Operand::Macro(value)
+    }
+}
+
+impl<T> From<Vec<T>> for Operand
+where
+    T: Into<Operand>,
+{
+    fn from(values: Vec<T>) -> Self {
+        Operand::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// A `[Field] <operator> <operand>` condition
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    pub field: String,
+    pub operator: Operator,
+    pub operand: Operand,
+}
+
+impl Condition {
+    pub fn new<F, O>(field: F, operator: Operator, operand: O) -> Self
+    where
+        F: Into<String>,
+        O: Into<Operand>,
+    {
+        Condition {
+            field: field.into(),
+            operator,
+            operand: operand.into(),
+        }
+    }
+}
+
+impl Condition {
+    fn render(&self, time_precision: bool) -> String {
+        format!(
+            "{} {} {}",
+            field_ref(&self.field),
+            self.operator,
+            self.operand.render(time_precision)
+        )
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(false).fmt(f)
+    }
+}
+
+/// `[Source].[field]`, to filter on the source of a link query
+pub fn source(field: &str) -> String {
+    format!("[Source].{}", field_ref(field))
+}
+
+/// `[Target].[field]`, to filter on the target of a link query
+pub fn target(field: &str) -> String {
+    format!("[Target].{}", field_ref(field))
+}
+
+/// `[System.Links.LinkType]`, to filter link queries by link type
+pub const LINK_TYPE: &str = "System.Links.LinkType";
+
+/// brackets a field reference name, unless it already is
+fn field_ref(field: &str) -> String {
+    if field.starts_with('[') {
+        field.to_owned()
+    } else {
+        format!("[{}]", field)
+    }
+}
+
+#[derive(Default)]
+pub struct WiqlBuilder {
+    fields: Vec<String>,
+    source: WiqlSource,
+    /// groups of conditions ORed together, the groups being ANDed
+    conditions: Vec<Vec<Condition>>,
+    order_by: Vec<(String, SortDirection)>,
+    as_of: Option<DateTime<Utc>>,
+    mode: Option<LinkMode>,
+    time_precision: bool,
+}
+
+impl WiqlBuilder {
+    /// fields to return as columns, `System.Id` when none are selected
+    pub fn select<I, F>(&mut self, fields: I) -> &mut Self
+    where
+        I: IntoIterator<Item = F>,
+        F: Into<String>,
+    {
+        self.fields.extend(fields.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn from(&mut self, source: WiqlSource) -> &mut Self {
+        self.source = source;
+        self
+    }
+
+    /// add a condition, ANDed with the others
+    pub fn filter<F, O>(&mut self, field: F, operator: Operator, operand: O) -> &mut Self
+    where
+        F: Into<String>,
+        O: Into<Operand>,
+    {
+        self.condition(Condition::new(field, operator, operand))
+    }
+
+    pub fn condition(&mut self, condition: Condition) -> &mut Self {
+        self.conditions.push(vec![condition]);
+        self
+    }
+
+    /// add a group of conditions, ORed together and ANDed with the others
+    pub fn any<I>(&mut self, conditions: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Condition>,
+    {
+        let conditions: Vec<Condition> = conditions.into_iter().collect();
+        if !conditions.is_empty() {
+            self.conditions.push(conditions);
+        }
+        self
+    }
+
+    pub fn order_by<F>(&mut self, field: F, direction: SortDirection) -> &mut Self
+    where
+        F: Into<String>,
+    {
+        self.order_by.push((field.into(), direction));
+        self
+    }
+
+    /// run the query against the work items as they were at the given date
+    pub fn as_of(&mut self, as_of: DateTime<Utc>) -> &mut Self {
+        self.as_of = Some(as_of);
+        self
+    }
+
+    /// mode of a `WorkItemLinks` query
+    pub fn mode(&mut self, mode: LinkMode) -> &mut Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// compare dates with their time, instead of their day only
+    ///
+    /// The query then runs with `timePrecision`, which Azure requires for dates with a time.
+    pub fn time_precision(&mut self, time_precision: bool) -> &mut Self {
+        self.time_precision = time_precision;
+        self
+    }
+
+    pub fn build(&self) -> Wiql {
+        let fields: Vec<String> = if self.fields.is_empty() {
+            vec![field_ref("System.Id")]
+        } else {
+            self.fields.iter().map(|field| field_ref(field)).collect()
+        };
+        let mut query = format!("SELECT {} FROM {}", fields.join(", "), self.source);
+        if !self.conditions.is_empty() {
+            let conditions: Vec<String> = self
+                .conditions
+                .iter()
+                .map(|group| {
+                    let group: Vec<String> = group
+                        .iter()
+                        .map(|condition| condition.render(self.time_precision))
+                        .collect();
+                    if group.len() > 1 {
+                        format!("({})", group.join(" OR "))
+                    } else {
+                        group.join(" OR ")
+                    }
+                })
+                .collect();
+            query.push_str(" WHERE ");
+            query.push_str(&conditions.join(" AND "));
+        }
+        if !self.order_by.is_empty() {
+            let order_by: Vec<String> = self
+                .order_by
+                .iter()
+                .map(|(field, direction)| format!("{} {}", field_ref(field), direction))
+                .collect();
+            query.push_str(" ORDER BY ");
+            query.push_str(&order_by.join(", "));
+        }
+        if let Some(as_of) = self.as_of {
+            query.push_str(&format!(
+                " ASOF {}",
+                Operand::Date(as_of).render(self.time_precision)
+            ));
+        }
+        if let Some(mode) = self.mode {
+            query.push_str(&format!(" MODE ({})", mode));
+        }
+        Wiql {
+            query,
+            time_precision: self.time_precision,
+        }
+    }
+}
+
+/// Where and how a query runs
+#[derive(Clone, Default)]
+pub struct WiqlOptions {
+    pub(crate) project: Option<String>,
+    pub(crate) team: Option<String>,
+    params: HashMap<&'static str, String>,
+}
+
+impl WiqlOptions {
+    pub fn builder() -> WiqlOptionsBuilder {
+        WiqlOptionsBuilder::default()
+    }
+
+    /// serialize options as a string. returns None if no options are defined
+    pub fn serialize(&self) -> Option<String> {
+        if self.params.is_empty() {
+            None
+        } else {
+            let encoded: String = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&self.params)
+                .finish();
+            Some(encoded)
+        }
+    }
+
+    /// options to run `wiql` with, turning on time precision if its dates have a time
+    pub(crate) fn for_query(&self, wiql: &Wiql) -> WiqlOptions {
+        let mut options = self.clone();
+        if wiql.time_precision {
+            options.params.insert("timePrecision", true.to_string());
+        }
+        options
+    }
+}
+
+#[derive(Default)]
+pub struct WiqlOptionsBuilder(WiqlOptions);
+
+impl WiqlOptionsBuilder {
+    /// project the query runs in, required by `@project`
+    pub fn project<P>(&mut self, project: P) -> &mut Self
+    where
+        P: Into<String>,
+    {
+        self.0.project = Some(project.into());
+        self
+    }
+
+    /// team the query runs for, required by `@CurrentIteration`
+    pub fn team<T>(&mut self, team: T) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.0.team = Some(team.into());
+        self
+    }
+
+    /// maximum number of results
+    pub fn top(&mut self, top: u32) -> &mut Self {
+        self.0.params.insert("$top", top.to_string());
+        self
+    }
+
+    /// compare dates with their time, instead of their day only
+    pub fn time_precision(&mut self, time_precision: bool) -> &mut Self {
+        self.0
+            .params
+            .insert("timePrecision", time_precision.to_string());
+        self
+    }
+
+    pub fn build(&self) -> WiqlOptions {
+        self.0.clone()
+    }
+}
+
+/// enum representation of query types
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryType {
+    #[default]
+    Flat,
+    OneHop,
+    Tree,
+}
+
+/// enum representation of query result types
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryResultType {
+    #[default]
+    WorkItem,
+    WorkItemLink,
+}
+
+/// Result of a query
+///
+/// Flat queries list `work_items`, link queries list `work_item_relations` instead.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkItemQueryResult {
+    pub query_type: QueryType,
+    pub query_result_type: QueryResultType,
+    pub as_of: Option<String>,
+    pub columns: Vec<FieldReference>,
+    pub sort_columns: Vec<SortColumn>,
+    pub work_items: Vec<WorkItemRef>,
+    pub work_item_relations: Vec<WorkItemLink>,
+}
+
+impl WorkItemQueryResult {
+    /// ids of the resulting work items, in order and without duplicates
+    pub fn ids(&self) -> Vec<usize> {
+        let mut seen = std::collections::HashSet::new();
+        let refs = self.work_items.iter().chain(
+            self.work_item_relations
+                .iter()
+                .flat_map(|link| link.source.iter().chain(link.target.iter())),
+        );
+        refs.map(|work_item| work_item.id)
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+
+    /// `(parent, child)` ids of a tree query, with no parent for the roots
+    pub fn tree(&self) -> Vec<(Option<usize>, usize)> {
+        self.work_item_relations
+            .iter()
+            .filter_map(|link| {
+                let target = link.target.as_ref()?;
+                Some((link.source.as_ref().map(|source| source.id), target.id))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldReference {
+    pub name: Option<String>,
+    pub reference_name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SortColumn {
+    pub field: FieldReference,
+    pub descending: bool,
+}
+
+/// A link between two work items of a link query
+///
+/// Roots of tree queries have neither `rel` nor `source`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct WorkItemLink {
+    pub rel: Option<String>,
+    pub source: Option<WorkItemRef>,
+    pub target: Option<WorkItemRef>,
+}
+
+/// A saved query, from the queries API
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryHierarchyItem {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub wiql: Option<String>,
+    pub is_folder: Option<bool>,
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::work_items::field;
+    use crate::AzureClient;
+
+    #[test]
+    fn build_flat_query() {
+        let wiql = Wiql::builder()
+            .select(vec![field::ID, field::TITLE])
+            .filter(field::TEAM_PROJECT, Operator::Equal, Macro::Project)
+            .filter(field::TITLE, Operator::Contains, "it's broken")
+            .filter(
+                field::CHANGED_DATE,
+                Operator::GreaterOrEqual,
+                Macro::Today(-7),
+            )
+            .any(vec![
+                Condition::new(field::ASSIGNED_TO, Operator::Equal, Macro::Me),
+                Condition::new(field::STATE, Operator::In, vec!["New", "Active"]),
+            ])
+            .order_by(field::PRIORITY, SortDirection::Asc)
+            .order_by(field::CHANGED_DATE, SortDirection::Desc)
+            .as_of(Utc.with_ymd_and_hms(2020, 5, 1, 0, 0, 0).unwrap())
+            .build();
+        assert_eq!(
+            wiql.query,
+            "SELECT [System.Id], [System.Title] FROM WorkItems \
+             WHERE [System.TeamProject] = @project \
+             AND [System.Title] CONTAINS 'it''s broken' \
+             AND [System.ChangedDate] >= @Today - 7 \
+             AND ([System.AssignedTo] = @Me OR [System.State] IN ('New', 'Active')) \
+             ORDER BY [Microsoft.VSTS.Common.Priority] asc, [System.ChangedDate] desc \
+             ASOF '2020-05-01'"
+        );
+        let raw = "SELECT [System.Id] FROM WorkItems WHERE [System.Title] = \"x\"";
+        assert_eq!(
+            serde_json::to_value(Wiql::new(raw)).unwrap(),
+            serde_json::json!({ "query": raw })
+        );
+    }
+
+    #[test]
+    fn render_dates_with_time_precision() {
+        let changed = Utc.with_ymd_and_hms(2020, 5, 1, 10, 15, 30).unwrap();
+        let builder = || {
+            let mut builder = Wiql::builder();
+            builder
+                .filter(field::CHANGED_DATE, Operator::GreaterOrEqual, changed)
+                .as_of(changed);
+            builder
+        };
+        assert_eq!(
+            builder().build().query,
+            "SELECT [System.Id] FROM WorkItems \
+             WHERE [System.ChangedDate] >= '2020-05-01' \
+             ASOF '2020-05-01'"
+        );
+
+        let precise = builder().time_precision(true).build();
+        assert_eq!(
+            precise.query,
+            "SELECT [System.Id] FROM WorkItems \
+             WHERE [System.ChangedDate] >= '2020-05-01T10:15:30Z' \
+             ASOF '2020-05-01T10:15:30Z'"
+        );
+        let options = WiqlOptions::default().for_query(&precise);
+        assert_eq!(options.serialize().as_deref(), Some("timePrecision=true"));
+    }
+
+    #[test]
+    fn build_link_query() {
+        let wiql = Wiql::builder()
+            .from(WiqlSource::WorkItemLinks)
+            .filter(source(field::WORK_ITEM_TYPE), Operator::Equal, "Epic")
+            .filter(
+                LINK_TYPE,
+                Operator::Equal,
+                "System.LinkTypes.Hierarchy-Forward",
+            )
+            .mode(LinkMode::Recursive)
+            .build();
+        assert_eq!(
+            wiql.query,
+            "SELECT [System.Id] FROM WorkItemLinks \
+             WHERE [Source].[System.WorkItemType] = 'Epic' \
+             AND [System.Links.LinkType] = 'System.LinkTypes.Hierarchy-Forward' \
+             MODE (Recursive)"
+        );
+    }
+
+    #[test]
+    fn deserialize_tree_result() {
+        let result: WorkItemQueryResult = serde_json::from_value(serde_json::json!({
+            "queryType": "tree",
+            "queryResultType": "workItemLink",
+            "asOf": "2020-05-01T00:00:00Z",
+            "columns": [{ "referenceName": "System.Id", "name": "ID", "url": "" }],
+            "sortColumns": [{ "field": { "referenceName": "System.Id" }, "descending": true }],
+            "workItemRelations": [
+                { "rel": null, "source": null, "target": { "id": 1, "url": "" } },
+                { "rel": "System.LinkTypes.Hierarchy-Forward", "source": { "id": 1, "url": "" }, "target": { "id": 2, "url": "" } },
+                { "rel": "System.LinkTypes.Hierarchy-Forward", "source": { "id": 2, "url": "" }, "target": { "id": 3, "url": "" } }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(result.query_type, QueryType::Tree);
+        assert_eq!(result.query_result_type, QueryResultType::WorkItemLink);
+        assert!(result.sort_columns[0].descending);
+        assert_eq!(result.ids(), vec![1, 2, 3]);
+        assert_eq!(result.tree(), vec![(None, 1), (Some(1), 2), (Some(2), 3)]);
+    }
+
+    #[tokio::test]
+    async fn run_queries() {
+        let server = MockServer::start().await;
+        let result = ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "queryType": "flat",
+            "queryResultType": "workItem",
+            "workItems": [{ "id": 3, "url": "" }]
+        }));
+        Mock::given(method("POST"))
+            .and(path("/org/project/team/_apis/wit/wiql"))
+            .and(query_param("$top", "10"))
+            .and(body_json(
+                serde_json::json!({ "query": "SELECT [System.Id] FROM WorkItems" }),
+            ))
+            .respond_with(result.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/org/project/_apis/wit/queries/Shared%20Queries/Triage",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "8a8c8212-15ca-41ed-97aa-1d6fbfbcd581",
+                "name": "Triage",
+                "path": "Shared Queries/Triage"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/org/project/_apis/wit/wiql/8a8c8212-15ca-41ed-97aa-1d6fbfbcd581",
+            ))
+            .respond_with(result)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let options = WiqlOptions::builder()
+            .project("project")
+            .team("team")
+            .top(10)
+            .build();
+        let result = client
+            .query(&Wiql::builder().build(), &options)
+            .await
+            .unwrap();
+        assert_eq!(result.ids(), vec![3]);
+
+        let result = client
+            .saved_query_by_path("project", "Shared Queries/Triage", &WiqlOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(result.ids(), vec![3]);
+    }
+}
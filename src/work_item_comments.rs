@@ -0,0 +1,272 @@
+//! Work item comments
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/wit/comments?view=azure-devops-rest-5.1
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::identity::IdentityRef;
+use crate::SortDirection;
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub work_item_id: usize,
+    pub id: u64,
+    pub version: Option<u64>,
+    pub text: Option<String>,
+    pub rendered_text: Option<String>,
+    pub format: Option<String>,
+    pub created_by: Option<IdentityRef>,
+    pub created_date: Option<DateTime<Utc>>,
+    pub created_on_behalf_of: Option<IdentityRef>,
+    pub created_on_behalf_date: Option<DateTime<Utc>>,
+    pub modified_by: Option<IdentityRef>,
+    pub modified_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub is_deleted: bool,
+    #[serde(default)]
+    pub reactions: Vec<CommentReaction>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentReaction {
+    pub comment_id: Option<u64>,
+    #[serde(rename = "type")]
+    pub reaction_type: CommentReactionType,
+    #[serde(default)]
+    pub count: u32,
+    #[serde(default)]
+    pub is_current_user_engaged: bool,
+    pub url: Option<String>,
+}
+
+/// enum representation of the reactions a comment can get
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum CommentReactionType {
+    Like,
+    Dislike,
+    Heart,
+    Hooray,
+    Smile,
+    Confused,
+}
+
+impl fmt::Display for CommentReactionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CommentReactionType::Like => "like",
+            CommentReactionType::Dislike => "dislike",
+            CommentReactionType::Heart => "heart",
+            CommentReactionType::Hooray => "hooray",
+            CommentReactionType::Smile => "smile",
+            CommentReactionType::Confused => "confused",
+        }
+        .fmt(f)
+    }
+}
+
+/// A page of comments, pointing to the next one through `continuation_token`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CommentList {
+    pub(crate) comments: Vec<Comment>,
+    pub(crate) continuation_token: Option<String>,
+}
+
+/// Body of comment creation and edition requests
+#[derive(Debug, Serialize)]
+pub(crate) struct CommentText<'a> {
+    pub(crate) text: &'a str,
+}
+
+/// enum representation of the additional data comments can be returned with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CommentExpand {
+    None,
+    Reactions,
+    RenderedText,
+    RenderedTextOnly,
+    All,
+}
+
+impl fmt::Display for CommentExpand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CommentExpand::None => "none",
+            CommentExpand::Reactions => "reactions",
+            CommentExpand::RenderedText => "renderedText",
+            CommentExpand::RenderedTextOnly => "renderedTextOnly",
+            CommentExpand::All => "all",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Default)]
+pub struct CommentListOptions {
+    params: HashMap<&'static str, String>,
+}
+
+impl CommentListOptions {
+    pub fn builder() -> CommentListOptionsBuilder {
+        CommentListOptionsBuilder::default()
+    }
+
+    /// serialize options as a string. returns None if no options are defined
+    pub fn serialize(&self) -> Option<String> {
+        if self.params.is_empty() {
+            None
+        } else {
+            let encoded: String = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&self.params)
+                .finish();
+            Some(encoded)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CommentListOptionsBuilder(CommentListOptions);
+
+impl CommentListOptionsBuilder {
+    /// number of comments requested per page, up to 200
+    pub fn top(&mut self, top: u32) -> &mut Self {
+        self.0.params.insert("$top", top.to_string());
+        self
+    }
+
+    pub fn include_deleted(&mut self, include_deleted: bool) -> &mut Self {
+        self.0
+            .params
+            .insert("includeDeleted", include_deleted.to_string());
+        self
+    }
+
+    pub fn expand(&mut self, expand: CommentExpand) -> &mut Self {
+        self.0.params.insert("$expand", expand.to_string());
+        self
+    }
+
+    /// order by creation date, oldest first by default
+    pub fn order(&mut self, order: SortDirection) -> &mut Self {
+        self.0.params.insert("order", order.to_string());
+        self
+    }
+
+    pub fn build(&self) -> CommentListOptions {
+        CommentListOptions {
+            params: self.0.params.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::AzureClient;
+
+    fn comment(id: u64) -> serde_json::Value {
+        serde_json::json!({
+            "workItemId": 7,
+            "id": id,
+            "version": 1,
+            "text": format!("comment {}", id),
+            "createdBy": { "displayName": "Jamie Doe" },
+            "createdDate": "2020-05-01T10:15:30.25Z",
+            "reactions": [{ "commentId": id, "type": "like", "count": 2 }]
+        })
+    }
+
+    #[tokio::test]
+    async fn stream_comments_with_continuation_tokens() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/org/project/_apis/wit/workItems/7/comments"))
+            .and(query_param("api-version", "5.1-preview.3"))
+            .and(query_param("continuationToken", "next"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "totalCount": 3,
+                "count": 1,
+                "comments": [comment(3)]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/project/_apis/wit/workItems/7/comments"))
+            .and(query_param("api-version", "5.1-preview.3"))
+            .and(query_param("order", "desc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "totalCount": 3,
+                "count": 2,
+                "comments": [comment(1), comment(2)],
+                "continuationToken": "next"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let options = CommentListOptions::builder()
+            .order(SortDirection::Desc)
+            .build();
+        let comments: Vec<Comment> = client
+            .work_item_comments("project", 7, &options)
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<u64> = comments.iter().map(|comment| comment.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(
+            comments[0].reactions[0].reaction_type,
+            CommentReactionType::Like
+        );
+        assert_eq!(comments[0].reactions[0].count, 2);
+    }
+
+    #[tokio::test]
+    async fn edit_comment_and_react() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/org/project/_apis/wit/workItems/7/comments/1"))
+            .and(body_json(serde_json::json!({ "text": "edited" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(comment(1)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(
+                "/org/project/_apis/wit/workItems/7/comments/1/reactions/heart",
+            ))
+            .and(query_param("api-version", "5.1-preview.1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "commentId": 1,
+                "type": "heart",
+                "count": 1,
+                "isCurrentUserEngaged": true
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        client
+            .edit_work_item_comment("project", 7, 1, "edited")
+            .await
+            .unwrap();
+        let reaction = client
+            .add_work_item_comment_reaction("project", 7, 1, CommentReactionType::Heart)
+            .await
+            .unwrap();
+        assert!(reaction.is_current_user_engaged);
+    }
+}
@@ -0,0 +1,379 @@
+//! Work item types, fields and states
+//!
+//! https://docs.microsoft.com/en-us/rest/api/azure/devops/wit/work%20item%20types?view=azure-devops-rest-5.1
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::work_items::WorkItemFields;
+use crate::{AzureClient, Future};
+
+/// Work item tracking metadata of a project
+pub struct WorkItemTypes {
+    ops: AzureClient,
+    project: String,
+}
+
+impl WorkItemTypes {
+    #[doc(hidden)]
+    pub fn new<P>(ops: AzureClient, project: P) -> Self
+    where
+        P: Into<String>,
+    {
+        WorkItemTypes {
+            ops,
+            project: project.into(),
+        }
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/workitemtypes?api-version=5.1
+    pub fn list(&self) -> Future<Vec<WorkItemType>> {
+        self.ops.get_values(&self.path(""))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/workitemtypes/{type}?api-version=5.1
+    pub fn get(&self, work_item_type: &str) -> Future<WorkItemType> {
+        self.ops.get(&self.path(&format!("/{}", work_item_type)))
+    }
+
+    /// Fields of a work item type, along with their allowed values
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/workitemtypes/{type}/fields?$expand=allowedValues&api-version=5.1
+    pub fn fields(&self, work_item_type: &str) -> Future<Vec<WorkItemTypeField>> {
+        self.ops
+            .get_values(&self.path(&format!("/{}/fields?$expand=allowedValues", work_item_type)))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/workitemtypes/{type}/fields/{field}?$expand=allowedValues&api-version=5.1
+    pub fn field(&self, work_item_type: &str, field: &str) -> Future<WorkItemTypeField> {
+        self.ops.get(&self.path(&format!(
+            "/{}/fields/{}?$expand=allowedValues",
+            work_item_type, field
+        )))
+    }
+
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/workitemtypes/{type}/states?api-version=5.1
+    pub fn states(&self, work_item_type: &str) -> Future<Vec<WorkItemStateColor>> {
+        self.ops
+            .get_values(&self.path(&format!("/{}/states", work_item_type)))
+    }
+
+    /// Every field of the project, whichever work item type it belongs to
+    ///
+    /// GET https://dev.azure.com/{organization}/{project}/_apis/wit/fields?api-version=5.1
+    pub fn all_fields(&self) -> Future<Vec<WorkItemField>> {
+        self.ops.get_values(&format!(
+            "/{}/{}/_apis/wit/fields",
+            self.ops.org, self.project
+        ))
+    }
+
+    fn path(&self, more: &str) -> String {
+        format!(
+            "/{}/{}/_apis/wit/workitemtypes{}",
+            self.ops.org, self.project, more
+        )
+    }
+}
+
+/// https://docs.microsoft.com/en-us/rest/api/azure/devops/wit/work%20item%20types/get?view=azure-devops-rest-5.1#workitemtype
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemType {
+    pub name: String,
+    pub reference_name: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<WorkItemIcon>,
+    #[serde(default)]
+    pub is_disabled: bool,
+    #[serde(default)]
+    pub fields: Vec<WorkItemTypeFieldInstance>,
+    #[serde(default)]
+    pub states: Vec<WorkItemStateColor>,
+    /// transitions from each state, the initial one being keyed by an empty string
+    pub transitions: Option<HashMap<String, Vec<WorkItemStateTransition>>>,
+    pub url: Option<String>,
+}
+
+impl WorkItemType {
+    /// states a work item can move to from `from`, `""` being the state of new work items
+    pub fn allowed_transitions(&self, from: &str) -> Vec<&str> {
+        self.transitions
+            .as_ref()
+            .and_then(|transitions| transitions.get(from))
+            .map(|transitions| transitions.iter().map(|t| t.to.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn can_transition(&self, from: &str, to: &str) -> bool {
+        from == to || self.allowed_transitions(from).contains(&to)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct WorkItemIcon {
+    pub id: String,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemTypeFieldInstance {
+    pub name: String,
+    pub reference_name: String,
+    #[serde(default)]
+    pub always_required: bool,
+    pub default_value: Option<Value>,
+    pub help_text: Option<String>,
+    pub url: Option<String>,
+}
+
+/// A field of a work item type, with the values it may take
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemTypeField {
+    pub name: String,
+    pub reference_name: String,
+    #[serde(default)]
+    pub always_required: bool,
+    pub default_value: Option<Value>,
+    /// empty when any value is allowed
+    #[serde(default)]
+    pub allowed_values: Vec<Value>,
+    pub help_text: Option<String>,
+    #[serde(default)]
+    pub dependent_fields: Vec<WorkItemFieldReference>,
+    pub url: Option<String>,
+}
+
+impl WorkItemTypeField {
+    /// check a value against the field's rules, `None` standing for a missing value
+    pub fn validate(&self, value: Option<&Value>) -> Option<FieldViolation> {
+        match value {
+            None | Some(Value::Null) if self.always_required => Some(FieldViolation::Missing {
+                field: self.reference_name.clone(),
+            }),
+            Some(value) if !value.is_null() && !self.allows(value) => {
+                Some(FieldViolation::NotAllowed {
+                    field: self.reference_name.clone(),
+                    value: value.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// whether the field may take `value`, comparing numbers and strings by their text
+    pub fn allows(&self, value: &Value) -> bool {
+        self.allowed_values.is_empty()
+            || self
+                .allowed_values
+                .iter()
+                .any(|allowed| allowed == value || same_text(allowed, value))
+    }
+}
+
+fn same_text(a: &Value, b: &Value) -> bool {
+    let text = |value: &Value| match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    };
+    matches!((text(a), text(b)), (Some(a), Some(b)) if a == b)
+}
+
+/// Check `values` against the fields of a work item type before creating or updating a work item
+pub fn validate(fields: &[WorkItemTypeField], values: &WorkItemFields) -> Vec<FieldViolation> {
+    fields
+        .iter()
+        .filter_map(|field| field.validate(values.get(&field.reference_name)))
+        .collect()
+}
+
+/// A rule of a work item type field that a value breaks
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldViolation {
+    Missing { field: String },
+    NotAllowed { field: String, value: Value },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemFieldReference {
+    pub name: Option<String>,
+    pub reference_name: String,
+    pub url: Option<String>,
+}
+
+/// A state of a work item type and the category it belongs to, e.g. `Proposed` or `Completed`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemStateColor {
+    pub name: String,
+    pub color: Option<String>,
+    pub category: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemStateTransition {
+    pub to: String,
+    pub actions: Option<Vec<String>>,
+}
+
+/// A field definition, shared by all the work item types using it
+///
+/// https://docs.microsoft.com/en-us/rest/api/azure/devops/wit/fields/list?view=azure-devops-rest-5.1#workitemfield
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemField {
+    pub name: String,
+    pub reference_name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub field_type: Option<FieldType>,
+    pub usage: Option<String>,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub can_sort_by: bool,
+    #[serde(default)]
+    pub is_queryable: bool,
+    #[serde(default)]
+    pub is_identity: bool,
+    #[serde(default)]
+    pub is_picklist: bool,
+    pub picklist_id: Option<String>,
+    #[serde(default)]
+    pub supported_operations: Vec<WorkItemFieldOperation>,
+    pub url: Option<String>,
+}
+
+/// enum representation of field data types
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldType {
+    String,
+    Integer,
+    DateTime,
+    PlainText,
+    Html,
+    TreePath,
+    History,
+    Double,
+    Guid,
+    Boolean,
+    Identity,
+    PicklistInteger,
+    PicklistString,
+    PicklistDouble,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemFieldOperation {
+    pub name: String,
+    pub reference_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    fn bug() -> WorkItemType {
+        serde_json::from_value(serde_json::json!({
+            "name": "Bug",
+            "referenceName": "Microsoft.VSTS.WorkItemTypes.Bug",
+            "color": "CC293D",
+            "icon": { "id": "icon_insect" },
+            "isDisabled": false,
+            "states": [
+                { "name": "New", "color": "b2b2b2", "category": "Proposed" },
+                { "name": "Active", "color": "007acc", "category": "InProgress" },
+                { "name": "Closed", "color": "339933", "category": "Completed" }
+            ],
+            "transitions": {
+                "": [{ "to": "New", "actions": null }],
+                "New": [{ "to": "Active" }, { "to": "Closed" }],
+                "Active": [{ "to": "New", "actions": ["Microsoft.VSTS.Actions.StopWork"] }]
+            },
+            "fields": [{
+                "name": "Title",
+                "referenceName": "System.Title",
+                "alwaysRequired": true
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn state_transitions() {
+        let bug = bug();
+        assert_eq!(bug.allowed_transitions(""), vec!["New"]);
+        assert!(bug.can_transition("New", "Closed"));
+        assert!(bug.can_transition("Active", "Active"));
+        assert!(!bug.can_transition("Active", "Closed"));
+        assert!(bug.allowed_transitions("Removed").is_empty());
+    }
+
+    #[tokio::test]
+    async fn validate_against_type_fields() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/org/project/_apis/wit/workitemtypes/Bug/fields"))
+            .and(query_param("$expand", "allowedValues"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 3,
+                "value": [
+                    { "name": "Title", "referenceName": "System.Title", "alwaysRequired": true },
+                    {
+                        "name": "Priority",
+                        "referenceName": "Microsoft.VSTS.Common.Priority",
+                        "alwaysRequired": false,
+                        "allowedValues": [1, 2, 3, 4]
+                    },
+                    {
+                        "name": "Severity",
+                        "referenceName": "Microsoft.VSTS.Common.Severity",
+                        "alwaysRequired": false,
+                        "allowedValues": ["1 - Critical", "2 - High"]
+                    }
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let fields = client
+            .project("project")
+            .work_item_types()
+            .fields("Bug")
+            .await
+            .unwrap();
+        let values: WorkItemFields = serde_json::from_value(serde_json::json!({
+            "Microsoft.VSTS.Common.Priority": "2",
+            "Microsoft.VSTS.Common.Severity": "5 - Trivial"
+        }))
+        .unwrap();
+        assert_eq!(
+            validate(&fields, &values),
+            vec![
+                FieldViolation::Missing {
+                    field: "System.Title".into()
+                },
+                FieldViolation::NotAllowed {
+                    field: "Microsoft.VSTS.Common.Severity".into(),
+                    value: "5 - Trivial".into()
+                }
+            ]
+        );
+    }
+}
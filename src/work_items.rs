@@ -1,12 +1,25 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use percent_encoding::percent_decode_str;
+use serde::de::DeserializeOwned;
 use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use url::form_urlencoded;
+
+use crate::identity::IdentityRef;
+use crate::Result;
 
+/// Work items referenced by a flat query result
+#[deprecated(note = "use `wiql::WorkItemQueryResult` instead")]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkItems {
     pub work_items: Vec<WorkItemRef>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct WorkItemRef {
     pub id: usize,
     pub url: String
@@ -15,13 +28,1235 @@ pub struct WorkItemRef {
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct WorkItem {
     pub id: usize,
+    pub rev: Option<u64>,
+    #[serde(default)]
     pub fields: WorkItemFields,
+    /// only returned when relations are expanded, see `WorkItemExpand`
+    pub relations: Option<Vec<WorkItemRelation>>,
+    pub url: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub struct WorkItemFields {
-    #[serde(rename = "System.Title")]
-    pub title: String,
-    #[serde(rename = "Custom.Jira")]
-    pub jira: Option<String>,
-}
\ No newline at end of file
+impl WorkItem {
+    /// deserialize the fields into a user provided struct, e.g. one with `#[serde(rename = "Custom.Jira")]` fields
+    pub fn fields_as<T>(&self) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(T::deserialize(self.fields.to_value())?)
+    }
+
+    /// relations of the given type, empty unless relations were expanded
+    pub fn relations_of(&self, relation_type: &WorkItemRelationType) -> Vec<&WorkItemRelation> {
+        self.relations
+            .iter()
+            .flatten()
+            .filter(|relation| relation.relation_type() == *relation_type)
+            .collect()
+    }
+
+    /// position of a relation in `relations`, as expected by `JsonPatchBuilder::remove_relation`
+    pub fn relation_index<F>(&self, predicate: F) -> Option<usize>
+    where
+        F: Fn(&WorkItemRelation) -> bool,
+    {
+        self.relations.as_ref()?.iter().position(predicate)
+    }
+
+    pub fn parent_id(&self) -> Option<usize> {
+        self.relations_of(&WorkItemRelationType::Parent)
+            .first()
+            .and_then(|relation| relation.work_item_id())
+    }
+
+    pub fn child_ids(&self) -> Vec<usize> {
+        self.related_ids(&WorkItemRelationType::Child)
+    }
+
+    /// ids of the work items linked by relations of the given type
+    pub fn related_ids(&self, relation_type: &WorkItemRelationType) -> Vec<usize> {
+        self.relations_of(relation_type)
+            .into_iter()
+            .filter_map(WorkItemRelation::work_item_id)
+            .collect()
+    }
+}
+
+/// Which parts of a work item to return besides its fields
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorkItemExpand {
+    None,
+    Relations,
+    Fields,
+    Links,
+    All,
+}
+
+impl fmt::Display for WorkItemExpand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            WorkItemExpand::None => "none",
+            WorkItemExpand::Relations => "relations",
+            WorkItemExpand::Fields => "fields",
+            WorkItemExpand::Links => "links",
+            WorkItemExpand::All => "all",
+        }
+        .fmt(f)
+    }
+}
+
+impl From<WorkItemExpand> for String {
+    fn from(expand: WorkItemExpand) -> String {
+        expand.to_string()
+    }
+}
+
+/// A link from a work item to another work item, a hyperlink, an artifact or an attachment
+///
+/// https://docs.microsoft.com/en-us/rest/api/azure/devops/wit/work%20items/get%20work%20item?view=azure-devops-rest-5.1#workitemrelation
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkItemRelation {
+    pub rel: String,
+    pub url: String,
+    #[serde(default)]
+    pub attributes: HashMap<String, Value>,
+}
+
+impl WorkItemRelation {
+    pub fn relation_type(&self) -> WorkItemRelationType {
+        WorkItemRelationType::from(self.rel.as_str())
+    }
+
+    /// id of the linked work item, for work item links
+    pub fn work_item_id(&self) -> Option<usize> {
+        if !self.url.contains("/_apis/wit/workItems/") {
+            return None;
+        }
+        self.url.rsplit('/').next()?.parse().ok()
+    }
+
+    /// the linked commit, pull request or build, for artifact links
+    pub fn artifact(&self) -> Option<Artifact> {
+        match self.relation_type() {
+            WorkItemRelationType::ArtifactLink => Artifact::parse(&self.url),
+            _ => None,
+        }
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.attributes.get("comment").and_then(Value::as_str)
+    }
+
+    /// the link name, e.g. `Parent`, `Fixed in Commit` or the file name of an attachment
+    pub fn name(&self) -> Option<&str> {
+        self.attributes.get("name").and_then(Value::as_str)
+    }
+}
+
+/// enum representation of the reference names of common relation types
+///
+/// https://docs.microsoft.com/en-us/azure/devops/boards/queries/link-type-reference
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WorkItemRelationType {
+    Parent,
+    Child,
+    Related,
+    Predecessor,
+    Successor,
+    Duplicate,
+    DuplicateOf,
+    Hyperlink,
+    ArtifactLink,
+    AttachedFile,
+    Other(String),
+}
+
+impl WorkItemRelationType {
+    pub fn rel(&self) -> &str {
+        match self {
+            WorkItemRelationType::Parent => "System.LinkTypes.Hierarchy-Reverse",
+            WorkItemRelationType::Child => "System.LinkTypes.Hierarchy-Forward",
+            WorkItemRelationType::Related => "System.LinkTypes.Related",
+            WorkItemRelationType::Predecessor => "System.LinkTypes.Dependency-Reverse",
+            WorkItemRelationType::Successor => "System.LinkTypes.Dependency-Forward",
+            WorkItemRelationType::Duplicate => "System.LinkTypes.Duplicate-Forward",
+            WorkItemRelationType::DuplicateOf => "System.LinkTypes.Duplicate-Reverse",
+            WorkItemRelationType::Hyperlink => "Hyperlink",
+            WorkItemRelationType::ArtifactLink => "ArtifactLink",
+            WorkItemRelationType::AttachedFile => "AttachedFile",
+            WorkItemRelationType::Other(rel) => rel,
+        }
+    }
+}
+
+impl From<&str> for WorkItemRelationType {
+    fn from(rel: &str) -> Self {
+        match rel {
+            "System.LinkTypes.Hierarchy-Reverse" => WorkItemRelationType::Parent,
+            "System.LinkTypes.Hierarchy-Forward" => WorkItemRelationType::Child,
+            "System.LinkTypes.Related" => WorkItemRelationType::Related,
+            "System.LinkTypes.Dependency-Reverse" => WorkItemRelationType::Predecessor,
+            "System.LinkTypes.Dependency-Forward" => WorkItemRelationType::Successor,
+            "System.LinkTypes.Duplicate-Forward" => WorkItemRelationType::Duplicate,
+            "System.LinkTypes.Duplicate-Reverse" => WorkItemRelationType::DuplicateOf,
+            "Hyperlink" => WorkItemRelationType::Hyperlink,
+            "ArtifactLink" => WorkItemRelationType::ArtifactLink,
+            "AttachedFile" => WorkItemRelationType::AttachedFile,
+            other => WorkItemRelationType::Other(other.to_owned()),
+        }
+    }
+}
+
+/// A commit, pull request or build, as identified by the `vstfs:///` uri of an artifact link
+///
+/// https://docs.microsoft.com/en-us/azure/devops/boards/queries/link-type-reference#external-link-type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Artifact {
+    Commit {
+        project_id: String,
+        repository_id: String,
+        commit_id: String,
+    },
+    PullRequest {
+        project_id: String,
+        repository_id: String,
+        pull_request_id: u64,
+    },
+    Build {
+        build_id: u64,
+    },
+}
+
+impl Artifact {
+    /// parse a `vstfs:///{tool}/{type}/{id}` uri, returning `None` for unsupported artifacts
+    pub fn parse(uri: &str) -> Option<Self> {
+        let mut parts = uri.strip_prefix("vstfs:///")?.splitn(3, '/');
+        let (tool, kind) = (parts.next()?, parts.next()?);
+        let id = percent_decode_str(parts.next()?).decode_utf8().ok()?;
+        let mut ids = id.splitn(3, '/');
+        match (tool, kind) {
+            ("Git", "Commit") => Some(Artifact::Commit {
+                project_id: ids.next()?.to_owned(),
+                repository_id: ids.next()?.to_owned(),
+                commit_id: ids.next()?.to_owned(),
+            }),
+            ("Git", "PullRequestId") => Some(Artifact::PullRequest {
+                project_id: ids.next()?.to_owned(),
+                repository_id: ids.next()?.to_owned(),
+                pull_request_id: ids.next()?.parse().ok()?,
+            }),
+            ("Build", "Build") => Some(Artifact::Build {
+                build_id: id.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn uri(&self) -> String {
+        match self {
+            Artifact::Commit {
+                project_id,
+                repository_id,
+                commit_id,
+            } => format!(
+                "vstfs:///Git/Commit/{}%2F{}%2F{}",
+                project_id, repository_id, commit_id
+            ),
+            Artifact::PullRequest {
+                project_id,
+                repository_id,
+                pull_request_id,
+            } => format!(
+                "vstfs:///Git/PullRequestId/{}%2F{}%2F{}",
+                project_id, repository_id, pull_request_id
+            ),
+            Artifact::Build { build_id } => format!("vstfs:///Build/Build/{}", build_id),
+        }
+    }
+
+    /// the name Azure expects in the attributes of a link to this artifact
+    pub fn link_name(&self) -> &'static str {
+        match self {
+            Artifact::Commit { .. } => "Fixed in Commit",
+            Artifact::PullRequest { .. } => "Pull Request",
+            Artifact::Build { .. } => "Build",
+        }
+    }
+}
+
+/// A single revision of a work item, with the fields and relations changed by it
+///
+/// https://docs.microsoft.com/en-us/rest/api/azure/devops/wit/updates/list?view=azure-devops-rest-5.1#workitemupdate
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemUpdate {
+    pub id: u64,
+    pub work_item_id: usize,
+    pub rev: u64,
+    pub revised_by: Option<IdentityRef>,
+    pub revised_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub fields: HashMap<String, FieldChange>,
+    pub relations: Option<RelationChanges>,
+    pub url: Option<String>,
+}
+
+impl WorkItemUpdate {
+    /// the change to a field, if this update changed it
+    pub fn field(&self, field: &str) -> Option<&FieldChange> {
+        self.fields.get(field)
+    }
+
+    /// old and new state, if this update changed the state
+    pub fn state_change(&self) -> Option<(Option<&str>, Option<&str>)> {
+        self.field(field::STATE)
+            .map(|change| (change.old_str(), change.new_str()))
+    }
+
+    /// when the update was made, `revised_date` being when the next one superseded it
+    pub fn changed_date(&self) -> Option<DateTime<Utc>> {
+        self.field(field::CHANGED_DATE)
+            .and_then(|change| change.new_str())
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc))
+    }
+}
+
+/// Old and new value of a field, missing respectively when the field was added or removed
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+}
+
+impl FieldChange {
+    pub fn old_str(&self) -> Option<&str> {
+        self.old_value.as_ref().and_then(Value::as_str)
+    }
+
+    pub fn new_str(&self) -> Option<&str> {
+        self.new_value.as_ref().and_then(Value::as_str)
+    }
+}
+
+/// Relations added, removed or updated by a work item update
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RelationChanges {
+    pub added: Vec<WorkItemRelation>,
+    pub removed: Vec<WorkItemRelation>,
+    pub updated: Vec<WorkItemRelation>,
+}
+
+/// Reference to an uploaded attachment, to be linked to work items with `AzureClient::attach`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AttachmentReference {
+    pub id: String,
+    pub url: String,
+}
+
+/// Reference names of well known fields
+///
+/// https://docs.microsoft.com/en-us/azure/devops/boards/work-items/guidance/work-item-field
+pub mod field {
+    pub const ID: &str = "System.Id";
+    pub const TITLE: &str = "System.Title";
+    pub const WORK_ITEM_TYPE: &str = "System.WorkItemType";
+    pub const STATE: &str = "System.State";
+    pub const REASON: &str = "System.Reason";
+    pub const AREA_PATH: &str = "System.AreaPath";
+    pub const ITERATION_PATH: &str = "System.IterationPath";
+    pub const TEAM_PROJECT: &str = "System.TeamProject";
+    pub const ASSIGNED_TO: &str = "System.AssignedTo";
+    pub const CREATED_BY: &str = "System.CreatedBy";
+    pub const CREATED_DATE: &str = "System.CreatedDate";
+    pub const CHANGED_BY: &str = "System.ChangedBy";
+    pub const CHANGED_DATE: &str = "System.ChangedDate";
+    pub const DESCRIPTION: &str = "System.Description";
+    pub const TAGS: &str = "System.Tags";
+    pub const HISTORY: &str = "System.History";
+    pub const PARENT: &str = "System.Parent";
+    pub const PRIORITY: &str = "Microsoft.VSTS.Common.Priority";
+    pub const SEVERITY: &str = "Microsoft.VSTS.Common.Severity";
+    pub const STACK_RANK: &str = "Microsoft.VSTS.Common.StackRank";
+    pub const BACKLOG_PRIORITY: &str = "Microsoft.VSTS.Common.BacklogPriority";
+    pub const ACCEPTANCE_CRITERIA: &str = "Microsoft.VSTS.Common.AcceptanceCriteria";
+    pub const CLOSED_DATE: &str = "Microsoft.VSTS.Common.ClosedDate";
+    pub const REPRO_STEPS: &str = "Microsoft.VSTS.TCM.ReproSteps";
+    pub const STORY_POINTS: &str = "Microsoft.VSTS.Scheduling.StoryPoints";
+    pub const EFFORT: &str = "Microsoft.VSTS.Scheduling.Effort";
+    pub const REMAINING_WORK: &str = "Microsoft.VSTS.Scheduling.RemainingWork";
+    pub const ORIGINAL_ESTIMATE: &str = "Microsoft.VSTS.Scheduling.OriginalEstimate";
+    pub const COMPLETED_WORK: &str = "Microsoft.VSTS.Scheduling.CompletedWork";
+}
+
+/// All the fields of a work item, keyed by reference name
+///
+/// Typed accessors return `None` both when a field is missing and when it doesn't have the expected type.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct WorkItemFields(pub HashMap<String, Value>);
+
+impl WorkItemFields {
+    pub fn get(&self, field: &str) -> Option<&Value> {
+        self.0.get(field)
+    }
+
+    pub fn contains(&self, field: &str) -> bool {
+        self.0.contains_key(field)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.0.iter()
+    }
+
+    /// deserialize a field, returning `Ok(None)` when it is missing
+    pub fn get_as<T>(&self, field: &str) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        match self.0.get(field) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => Ok(Some(T::deserialize(value)?)),
+        }
+    }
+
+    pub fn str(&self, field: &str) -> Option<&str> {
+        self.get(field).and_then(Value::as_str)
+    }
+
+    pub fn i64(&self, field: &str) -> Option<i64> {
+        self.get(field).and_then(Value::as_i64)
+    }
+
+    pub fn f64(&self, field: &str) -> Option<f64> {
+        self.get(field).and_then(Value::as_f64)
+    }
+
+    pub fn bool(&self, field: &str) -> Option<bool> {
+        self.get(field).and_then(Value::as_bool)
+    }
+
+    pub fn date(&self, field: &str) -> Option<DateTime<Utc>> {
+        self.str(field)
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc))
+    }
+
+    /// an identity field, either an identity reference or, on older servers, a `Name <domain\user>` string
+    pub fn identity(&self, field: &str) -> Option<IdentityRef> {
+        match self.get(field)? {
+            Value::String(name) => Some(IdentityRef {
+                display_name: Some(name.clone()),
+                ..Default::default()
+            }),
+            value => IdentityRef::deserialize(value).ok(),
+        }
+    }
+
+    pub fn html(&self, field: &str) -> Option<Html> {
+        self.str(field).map(|html| Html(html.to_owned()))
+    }
+
+    pub fn id(&self) -> Option<i64> {
+        self.i64(field::ID)
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.str(field::TITLE)
+    }
+
+    pub fn work_item_type(&self) -> Option<&str> {
+        self.str(field::WORK_ITEM_TYPE)
+    }
+
+    pub fn state(&self) -> Option<&str> {
+        self.str(field::STATE)
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.str(field::REASON)
+    }
+
+    pub fn area_path(&self) -> Option<&str> {
+        self.str(field::AREA_PATH)
+    }
+
+    pub fn iteration_path(&self) -> Option<&str> {
+        self.str(field::ITERATION_PATH)
+    }
+
+    pub fn team_project(&self) -> Option<&str> {
+        self.str(field::TEAM_PROJECT)
+    }
+
+    pub fn assigned_to(&self) -> Option<IdentityRef> {
+        self.identity(field::ASSIGNED_TO)
+    }
+
+    pub fn created_by(&self) -> Option<IdentityRef> {
+        self.identity(field::CREATED_BY)
+    }
+
+    pub fn created_date(&self) -> Option<DateTime<Utc>> {
+        self.date(field::CREATED_DATE)
+    }
+
+    pub fn changed_by(&self) -> Option<IdentityRef> {
+        self.identity(field::CHANGED_BY)
+    }
+
+    pub fn changed_date(&self) -> Option<DateTime<Utc>> {
+        self.date(field::CHANGED_DATE)
+    }
+
+    pub fn description(&self) -> Option<Html> {
+        self.html(field::DESCRIPTION)
+    }
+
+    /// tags, which Azure stores as a single `; ` separated string
+    pub fn tags(&self) -> Vec<&str> {
+        self.str(field::TAGS)
+            .map(|tags| {
+                tags.split(';')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn parent(&self) -> Option<i64> {
+        self.i64(field::PARENT)
+    }
+
+    pub fn priority(&self) -> Option<i64> {
+        self.i64(field::PRIORITY)
+    }
+
+    pub fn severity(&self) -> Option<&str> {
+        self.str(field::SEVERITY)
+    }
+
+    pub fn acceptance_criteria(&self) -> Option<Html> {
+        self.html(field::ACCEPTANCE_CRITERIA)
+    }
+
+    pub fn repro_steps(&self) -> Option<Html> {
+        self.html(field::REPRO_STEPS)
+    }
+
+    pub fn story_points(&self) -> Option<f64> {
+        self.f64(field::STORY_POINTS)
+    }
+
+    pub fn remaining_work(&self) -> Option<f64> {
+        self.f64(field::REMAINING_WORK)
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Object(
+            self.0
+                .iter()
+                .map(|(field, value)| (field.clone(), value.clone()))
+                .collect(),
+        )
+    }
+}
+
+/// Content of an HTML field, such as `System.Description`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Html(pub String);
+
+impl Html {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// the text content, without tags and with common entities decoded
+    pub fn to_text(&self) -> String {
+        let mut text = String::with_capacity(self.0.len());
+        let mut in_tag = false;
+        for c in self.0.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' if in_tag => in_tag = false,
+                c if !in_tag => text.push(c),
+                _ => {}
+            }
+        }
+        text.replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&")
+    }
+}
+
+/// Options of the work items batch API
+#[derive(Clone, Debug, Default)]
+pub struct WorkItemBatchOptions {
+    fields: Option<Vec<String>>,
+    as_of: Option<String>,
+    expand: Option<String>,
+    concurrency: Option<usize>,
+}
+
+/// Body of a `workitemsbatch` request
+#[derive(Debug, Serialize)]
+pub(crate) struct WorkItemBatchRequest<'a> {
+    ids: &'a [usize],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<&'a [String]>,
+    #[serde(rename = "asOf", skip_serializing_if = "Option::is_none")]
+    as_of: Option<&'a str>,
+    #[serde(rename = "$expand", skip_serializing_if = "Option::is_none")]
+    expand: Option<&'a str>,
+    #[serde(rename = "errorPolicy")]
+    error_policy: &'static str,
+}
+
+impl WorkItemBatchOptions {
+    pub fn builder() -> WorkItemBatchOptionsBuilder {
+        WorkItemBatchOptionsBuilder::default()
+    }
+
+    pub(crate) fn request<'a>(&'a self, ids: &'a [usize]) -> WorkItemBatchRequest<'a> {
+        WorkItemBatchRequest {
+            ids,
+            fields: self.fields.as_deref(),
+            as_of: self.as_of.as_deref(),
+            expand: self.expand.as_deref(),
+            error_policy: "omit",
+        }
+    }
+
+    pub(crate) fn concurrency(&self) -> usize {
+        self.concurrency.unwrap_or(4)
+    }
+
+    /// the same options as query parameters, to get work items one by one
+    pub(crate) fn serialize(&self) -> Option<String> {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        if let Some(fields) = &self.fields {
+            query.append_pair("fields", &fields.join(","));
+        }
+        if let Some(as_of) = &self.as_of {
+            query.append_pair("asOf", as_of);
+        }
+        if let Some(expand) = &self.expand {
+            query.append_pair("$expand", expand);
+        }
+        let encoded = query.finish();
+        if encoded.is_empty() {
+            None
+        } else {
+            Some(encoded)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct WorkItemBatchOptionsBuilder(WorkItemBatchOptions);
+
+impl WorkItemBatchOptionsBuilder {
+    /// only return these fields, can't be combined with `expand`
+    pub fn fields<I, F>(&mut self, fields: I) -> &mut Self
+    where
+        I: IntoIterator<Item = F>,
+        F: Into<String>,
+    {
+        self.0.fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// return the work items as they were at the given date
+    pub fn as_of(&mut self, as_of: DateTime<Utc>) -> &mut Self {
+        self.0.as_of = Some(as_of.to_rfc3339());
+        self
+    }
+
+    /// expand `relations`, `fields`, `links` or `all`, see `WorkItemExpand`
+    pub fn expand<E>(&mut self, expand: E) -> &mut Self
+    where
+        E: Into<String>,
+    {
+        self.0.expand = Some(expand.into());
+        self
+    }
+
+    /// maximum number of batches requested at the same time, 4 by default
+    pub fn concurrency(&mut self, concurrency: usize) -> &mut Self {
+        self.0.concurrency = Some(concurrency.max(1));
+        self
+    }
+
+    pub fn build(&self) -> WorkItemBatchOptions {
+        self.0.clone()
+    }
+}
+
+/// Result of deleting a work item
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkItemDelete {
+    pub id: usize,
+    pub code: Option<i32>,
+    pub deleted_by: Option<String>,
+    pub deleted_date: Option<String>,
+    pub name: Option<String>,
+    pub project: Option<String>,
+    #[serde(rename = "type")]
+    pub work_item_type: Option<String>,
+    pub url: Option<String>,
+}
+
+/// Query parameters of work item creation and updates
+#[derive(Default)]
+pub struct WorkItemUpdateOptions {
+    params: HashMap<&'static str, String>,
+}
+
+impl WorkItemUpdateOptions {
+    pub fn builder() -> WorkItemUpdateOptionsBuilder {
+        WorkItemUpdateOptionsBuilder::default()
+    }
+
+    /// serialize options as a string. returns None if no options are defined
+    pub fn serialize(&self) -> Option<String> {
+        if self.params.is_empty() {
+            None
+        } else {
+            let encoded: String = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&self.params)
+                .finish();
+            Some(encoded)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct WorkItemUpdateOptionsBuilder(WorkItemUpdateOptions);
+
+impl WorkItemUpdateOptionsBuilder {
+    /// validate the patch without saving the work item
+    pub fn validate_only(&mut self, validate_only: bool) -> &mut Self {
+        self.0
+            .params
+            .insert("validateOnly", validate_only.to_string());
+        self
+    }
+
+    /// skip work item type rules, such as allowed values and state transitions
+    pub fn bypass_rules(&mut self, bypass_rules: bool) -> &mut Self {
+        self.0
+            .params
+            .insert("bypassRules", bypass_rules.to_string());
+        self
+    }
+
+    /// don't notify subscribers of the change
+    pub fn suppress_notifications(&mut self, suppress_notifications: bool) -> &mut Self {
+        self.0
+            .params
+            .insert("suppressNotifications", suppress_notifications.to_string());
+        self
+    }
+
+    /// expand `relations`, `fields`, `links` or `all` in the returned work item, see `WorkItemExpand`
+    pub fn expand<E>(&mut self, expand: E) -> &mut Self
+    where
+        E: Into<String>,
+    {
+        self.0.params.insert("$expand", expand.into());
+        self
+    }
+
+    pub fn build(&self) -> WorkItemUpdateOptions {
+        WorkItemUpdateOptions {
+            params: self.0.params.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use futures::TryStreamExt;
+    use wiremock::matchers::{body_bytes, body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    use super::*;
+    use crate::{AzureClient, JsonPatch, WorkItemQueryResult};
+
+    fn work_item() -> WorkItem {
+        serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "rev": 3,
+            "fields": {
+                "System.Title": "Crash on startup",
+                "System.State": "Active",
+                "System.AssignedTo": {
+                    "displayName": "Jamie Doe",
+                    "uniqueName": "jamie@example.com",
+                    "id": "b9c2d3a1"
+                },
+                "System.CreatedBy": "Jamie Doe <EXAMPLE\\jamie>",
+                "System.CreatedDate": "2020-05-01T10:15:30.25Z",
+                "System.Description": "<div>Fails &amp; exits<br></div>",
+                "System.Tags": "crash; startup",
+                "Microsoft.VSTS.Common.Priority": 2,
+                "Custom.Jira": "ABC-123"
+            },
+            "url": "https://dev.azure.com/org/_apis/wit/workItems/7"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn well_known_fields() {
+        let fields = work_item().fields;
+        assert_eq!(fields.title(), Some("Crash on startup"));
+        assert_eq!(fields.state(), Some("Active"));
+        assert_eq!(fields.priority(), Some(2));
+        assert_eq!(fields.tags(), vec!["crash", "startup"]);
+        assert_eq!(
+            fields.assigned_to().unwrap().unique_name.as_deref(),
+            Some("jamie@example.com")
+        );
+        assert_eq!(
+            fields.created_by().unwrap().display_name.as_deref(),
+            Some("Jamie Doe <EXAMPLE\\jamie>")
+        );
+        assert_eq!(
+            fields.created_date().unwrap().to_rfc3339(),
+            "2020-05-01T10:15:30.250+00:00"
+        );
+        assert_eq!(fields.description().unwrap().to_text(), "Fails & exits");
+        assert_eq!(fields.str("Custom.Jira"), Some("ABC-123"));
+        assert_eq!(fields.severity(), None);
+        assert!(fields.get_as::<u32>(field::TITLE).is_err());
+    }
+
+    #[test]
+    fn missing_fields_deserialize() {
+        let work_item: WorkItem = serde_json::from_value(serde_json::json!({ "id": 1 })).unwrap();
+        assert_eq!(work_item.fields.title(), None);
+    }
+
+    #[test]
+    fn fields_as_user_struct() {
+        #[derive(Deserialize)]
+        struct Jira {
+            #[serde(rename = "System.Title")]
+            title: String,
+            #[serde(rename = "Custom.Jira")]
+            jira: Option<String>,
+        }
+
+        let jira: Jira = work_item().fields_as().unwrap();
+        assert_eq!(jira.title, "Crash on startup");
+        assert_eq!(jira.jira.as_deref(), Some("ABC-123"));
+    }
+
+    #[test]
+    fn typed_relations() {
+        let work_item: WorkItem = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "relations": [
+                {
+                    "rel": "System.LinkTypes.Hierarchy-Reverse",
+                    "url": "https://dev.azure.com/org/_apis/wit/workItems/1",
+                    "attributes": { "isLocked": false, "name": "Parent" }
+                },
+                {
+                    "rel": "System.LinkTypes.Hierarchy-Forward",
+                    "url": "https://dev.azure.com/org/_apis/wit/workItems/8",
+                    "attributes": { "name": "Child" }
+                },
+                {
+                    "rel": "ArtifactLink",
+                    "url": "vstfs:///Git/Commit/p1%2Fr1%2Fabc123",
+                    "attributes": { "name": "Fixed in Commit", "comment": "fix" }
+                },
+                { "rel": "Hyperlink", "url": "https://example.com" },
+                { "rel": "Custom.LinkTypes.Tests", "url": "https://dev.azure.com/org/_apis/wit/workItems/9" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(work_item.parent_id(), Some(1));
+        assert_eq!(work_item.child_ids(), vec![8]);
+        let relations = work_item.relations.as_ref().unwrap();
+        assert_eq!(relations[0].name(), Some("Parent"));
+        assert_eq!(relations[2].comment(), Some("fix"));
+        assert_eq!(
+            relations[2].artifact(),
+            Some(Artifact::Commit {
+                project_id: "p1".into(),
+                repository_id: "r1".into(),
+                commit_id: "abc123".into(),
+            })
+        );
+        assert_eq!(relations[3].work_item_id(), None);
+        assert_eq!(
+            relations[4].relation_type(),
+            WorkItemRelationType::Other("Custom.LinkTypes.Tests".into())
+        );
+        assert_eq!(
+            work_item.relation_index(|relation| relation.rel == "Hyperlink"),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn artifact_uris() {
+        for artifact in [
+            Artifact::Commit {
+                project_id: "p1".into(),
+                repository_id: "r1".into(),
+                commit_id: "abc123".into(),
+            },
+            Artifact::PullRequest {
+                project_id: "p1".into(),
+                repository_id: "r1".into(),
+                pull_request_id: 42,
+            },
+            Artifact::Build { build_id: 1234 },
+        ] {
+            assert_eq!(Artifact::parse(&artifact.uri()), Some(artifact));
+        }
+        assert_eq!(
+            Artifact::PullRequest {
+                project_id: "p1".into(),
+                repository_id: "r1".into(),
+                pull_request_id: 42,
+            }
+            .uri(),
+            "vstfs:///Git/PullRequestId/p1%2Fr1%2F42"
+        );
+        assert_eq!(Artifact::parse("vstfs:///Wiki/WikiPage/x"), None);
+        assert_eq!(Artifact::parse("https://example.com"), None);
+    }
+
+    #[tokio::test]
+    async fn stream_updates_across_pages() {
+        let server = MockServer::start().await;
+        let update = |rev: u64| {
+            serde_json::json!({
+                "id": rev,
+                "workItemId": 7,
+                "rev": rev,
+                "revisedBy": { "displayName": "Jamie Doe" },
+                "revisedDate": "9999-01-01T00:00:00Z",
+                "fields": {
+                    "System.State": { "oldValue": "New", "newValue": "Active" },
+                    "System.ChangedDate": {
+                        "oldValue": "2020-05-01T10:15:30.25Z",
+                        "newValue": "2020-05-02T08:00:00Z"
+                    },
+                    "System.Tags": { "newValue": "crash" }
+                }
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/wit/workItems/7/updates"))
+            .and(query_param("$skip", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 100,
+                "value": (1..=100).map(update).collect::<Vec<_>>()
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/wit/workItems/7/updates"))
+            .and(query_param("$skip", "100"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 1,
+                "value": [update(101)]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let updates: Vec<WorkItemUpdate> = client.work_item_updates(7).try_collect().await.unwrap();
+        assert_eq!(updates.len(), 101);
+        let last = updates.last().unwrap();
+        assert_eq!(last.rev, 101);
+        assert_eq!(last.state_change(), Some((Some("New"), Some("Active"))));
+        assert_eq!(last.field(field::TAGS).unwrap().old_value, None);
+        assert_eq!(
+            last.changed_date().unwrap().to_rfc3339(),
+            "2020-05-02T08:00:00+00:00"
+        );
+    }
+
+    #[tokio::test]
+    async fn unlink_removes_matching_relations_last_first() {
+        let server = MockServer::start().await;
+        let url = |id: usize| format!("{}/org/_apis/wit/workItems/{}", server.uri(), id);
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/wit/workItems/7"))
+            .and(query_param("$expand", "relations"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 7,
+                "rev": 5,
+                "relations": [
+                    { "rel": "System.LinkTypes.Related", "url": url(8) },
+                    { "rel": "System.LinkTypes.Related", "url": url(9) },
+                    { "rel": "System.LinkTypes.Related", "url": url(8) }
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/org/_apis/wit/workitems/7"))
+            .and(body_json(serde_json::json!([
+                { "op": "test", "path": "/rev", "value": 5 },
+                { "op": "remove", "path": "/relations/2" },
+                { "op": "remove", "path": "/relations/0" }
+            ])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 7,
+                "rev": 6,
+                "relations": [{ "rel": "System.LinkTypes.Related", "url": url(9) }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let work_item = client
+            .unlink_work_items(7, &WorkItemRelationType::Related, 8)
+            .await
+            .unwrap();
+        assert_eq!(
+            work_item.related_ids(&WorkItemRelationType::Related),
+            vec![9]
+        );
+    }
+
+    #[tokio::test]
+    async fn upload_and_download_attachments() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/org/project/_apis/wit/attachments"))
+            .and(query_param("fileName", "build log.txt"))
+            .and(header("Content-Type", "application/octet-stream"))
+            .and(body_bytes(b"build output".to_vec()))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "id": "a1",
+                "url": "https://dev.azure.com/org/_apis/wit/attachments/a1"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/wit/attachments/a1"))
+            .and(query_param("download", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"build output".to_vec()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/wit/attachments/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("not found"))
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let attachment = client
+            .upload_attachment("project", "build log.txt", "build output")
+            .await
+            .unwrap();
+        assert_eq!(attachment.id, "a1");
+
+        let chunks: Vec<_> = client.attachment("a1").try_collect().await.unwrap();
+        assert_eq!(chunks.concat(), b"build output".to_vec());
+
+        match client.attachment("missing").try_collect::<Vec<_>>().await {
+            Err(crate::Error(crate::ErrorKind::Fault { code, error }, _)) => {
+                assert_eq!(code.as_u16(), 404);
+                assert_eq!(error.message, "not found");
+            }
+            other => panic!("unexpected {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn create_work_item_sends_json_patch() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/org/project/_apis/wit/workitems/$Bug"))
+            .and(query_param("validateOnly", "true"))
+            .and(header("Content-Type", "application/json-patch+json"))
+            .and(body_json(serde_json::json!([
+                { "op": "add", "path": "/fields/System.Title", "value": "Crash" }
+            ])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 42,
+                "rev": 1,
+                "fields": { "System.Title": "Crash" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let work_item = client
+            .create_work_item(
+                "project",
+                "Bug",
+                &JsonPatch::builder()
+                    .add_field("System.Title", "Crash")
+                    .build(),
+                &WorkItemUpdateOptions::builder().validate_only(true).build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(work_item.id, 42);
+        assert_eq!(work_item.fields.title(), Some("Crash"));
+    }
+
+    /// answers batch requests with the requested work items in reverse order, omitting id 13
+    fn batch(request: &Request) -> ResponseTemplate {
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        assert!(body["ids"].as_array().unwrap().len() <= 200);
+        assert_eq!(body["fields"], serde_json::json!(["System.Title"]));
+        let value: Vec<Value> = body["ids"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|id| match id.as_u64() {
+                Some(13) => Value::Null,
+                _ => serde_json::json!({ "id": id, "fields": { "System.Title": "" } }),
+            })
+            .collect();
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "count": value.len(),
+            "value": value,
+        }))
+    }
+
+    #[tokio::test]
+    async fn query_work_items_in_batches() {
+        let server = MockServer::start().await;
+        let ids: Vec<usize> = (1..=450).rev().collect();
+        let refs: Vec<Value> = ids
+            .iter()
+            .map(|id| serde_json::json!({ "id": id, "url": "" }))
+            .collect();
+        Mock::given(method("POST"))
+            .and(path("/org/_apis/wit/wiql"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "workItems": refs })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/org/_apis/wit/workitemsbatch"))
+            .respond_with(batch)
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let options = WorkItemBatchOptions::builder()
+            .fields(vec![field::TITLE])
+            .build();
+        let work_items = client
+            .query_work_items_with(
+                r#"{ "query": "Select [System.Id] From WorkItems" }"#,
+                &options,
+            )
+            .await
+            .unwrap();
+        let expected: Vec<usize> = ids.into_iter().filter(|id| *id != 13).collect();
+        let actual: Vec<usize> = work_items.iter().map(|work_item| work_item.id).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn query_work_items_without_batch_api() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/org/_apis/wit/wiql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "workItems": [{ "id": 2, "url": "" }, { "id": 1, "url": "" }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/org/_apis/wit/workitemsbatch"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_json(serde_json::json!({ "message": "Not Found" })),
+            )
+            .mount(&server)
+            .await;
+        for id in 1..=2 {
+            Mock::given(method("GET"))
+                .and(path(format!("/org/_apis/wit/workItems/{}", id)))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": id })),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let work_items = client
+            .query_work_items(r#"{ "query": "Select [System.Id] From WorkItems" }"#)
+            .await
+            .unwrap();
+        let actual: Vec<usize> = work_items.iter().map(|work_item| work_item.id).collect();
+        assert_eq!(actual, vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn work_items_of_without_batch_api_keep_options() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/org/_apis/wit/workitemsbatch"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_json(serde_json::json!({ "message": "Not Found" })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/wit/workItems/3"))
+            .and(query_param("fields", "System.Title,System.State"))
+            .and(query_param("asOf", "2020-05-01T00:00:00+00:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 3,
+                "fields": { "System.Title": "Crash on start", "System.State": "Active" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/org/_apis/wit/workItems/4"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_json(serde_json::json!({ "message": "Not Found" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AzureClient::host(server.uri(), "azure-rust", "org", None).unwrap();
+        let result: WorkItemQueryResult = serde_json::from_value(serde_json::json!({
+            "workItems": [{ "id": 3, "url": "" }, { "id": 4, "url": "" }]
+        }))
+        .unwrap();
+        let options = WorkItemBatchOptions::builder()
+            .fields(vec![field::TITLE, field::STATE])
+            .as_of(Utc.with_ymd_and_hms(2020, 5, 1, 0, 0, 0).unwrap())
+            .build();
+        let work_items = client.work_items_of(&result, &options).await.unwrap();
+        let actual: Vec<usize> = work_items.iter().map(|work_item| work_item.id).collect();
+        assert_eq!(actual, vec![3]);
+    }
+}